@@ -1,10 +1,10 @@
-use std::{io::{Error}, fs::create_dir_all, collections::HashMap};
+use std::{io::{Error}, fs::create_dir_all, collections::BTreeMap};
 
 use askama::Template;
 
 use crate::{yaml::Data, types::music::{Album, Track, Artist}};
 
-use super::{template_write, Page, Meta, META_TYPE_ALBUM};
+use super::{template_write, Page, Meta, META_TYPE_ALBUM, playlist::build_playlist_files};
 
 #[derive(Template)]
 #[template(path = "album.html")]
@@ -13,7 +13,7 @@ struct TemplateAlbum<'a> {
     data: &'a Data,
     artist: &'a Artist,
     album: &'a Album,
-    tracks: HashMap<u8, Track>,
+    tracks: BTreeMap<u8, Track>,
 }
 
 
@@ -29,9 +29,10 @@ pub fn build_album(path: &str, data: &Data, album: &Album) -> Result<(), Error>
     let album_title = &album.name;
     let title = Some(album_title.to_owned() + " by " + &artist.name);
     let description = "By ".to_owned() + &artist.name;
-    let meta = Some(Meta { title: album_title.to_owned(), url: path_album, r#type: META_TYPE_ALBUM.to_owned(), image: album.cover_url.to_owned(), description });
+    let meta = Some(Meta { title: album_title.to_owned(), url: path_album.to_owned(), r#type: META_TYPE_ALBUM.to_owned(), image: album.cover_url.to_owned(), description });
     let page = Page { id_artist, id_album, id_track: None, title, meta };
-    let tracks = data.get_tracks_in_album(&album.id);
+    let tracks = data.get_tracks_in_album(&album.key());
+    build_playlist_files(&path_album, data, album_title, &data.get_tracks_in_album_sorted(&album.key()))?;
     let template = TemplateAlbum { page, data, artist, album, tracks };
     let content = template.render().unwrap();
     template_write(&content, &path_album_index)?;
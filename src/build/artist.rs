@@ -2,9 +2,9 @@ use std::{io::Error, sync::Arc, collections::HashMap, fs::create_dir_all};
 
 use askama::Template;
 
-use crate::{types::music::{Artist, Album, Track}, yaml::{Data, Country, Features}, Color, id_to_color};
+use crate::{types::music::{Artist, Album, Track}, yaml::{Data, Country, Features}, Color, id_to_color, playlist::by_release};
 
-use super::{template_write, Page};
+use super::{template_write, Page, playlist::build_playlist_files};
 
 
 #[derive(Template)]
@@ -16,9 +16,14 @@ struct TemplateArtists<'a> {
     country: Option<&'a Country>,
     albums: Vec<Album>,
     collectives: Vec<String>,
+    /// Most frequent collaborators with their number of shared tracks.
+    collaborators: Vec<(String, usize)>,
     color: String,
 }
 
+/// How many collaborators are listed under connections.
+const COLLABORATORS_MAX: usize = 10;
+
 pub fn build_artist(path: &str, data: &Data, id_artist: &str) -> Result<(), Error> {
     let path_artist = path.to_owned() + &id_artist;
     create_dir_all(&path_artist)?;
@@ -31,7 +36,8 @@ pub fn build_artist(path: &str, data: &Data, id_artist: &str) -> Result<(), Erro
 
     let albums = data.get_albums_by(id_artist);
 
-    let tracks = data.get_tracks_by(id_artist);
+    let tracks = by_release(data, data.get_tracks_by(id_artist).into_values().flatten().collect());
+    build_playlist_files(&(path_artist.to_owned() + "/"), data, &artist.name, &tracks)?;
 
     // let mut features = HashMap::new();
     // let is_own_album = |id: &str| albums.iter().map(|album| &album.id).any(|id_album| id == id_album );
@@ -41,6 +47,8 @@ pub fn build_artist(path: &str, data: &Data, id_artist: &str) -> Result<(), Erro
     // }
 
     let collectives = data.get_collectives_active(id_artist);
+    let mut collaborators = data.get_collaborators(id_artist);
+    collaborators.truncate(COLLABORATORS_MAX);
 
     let color = id_to_color(id_artist).hex();
     let page = Page {
@@ -57,6 +65,7 @@ pub fn build_artist(path: &str, data: &Data, id_artist: &str) -> Result<(), Erro
         country,
         albums,
         collectives,
+        collaborators,
         color,
     };
     let content = template.render().unwrap();
@@ -2,9 +2,9 @@ use std::io::Error;
 
 use askama::Template;
 
-use crate::{types::music::Artist, yaml::Data};
+use crate::{types::music::Artist, yaml::{Data, sort_artists}};
 
-use super::{template_write, Page};
+use super::{template_write, Page, letters::{group_by_letter, nav, Letter, Nav, LETTER_PAGES_FROM}};
 
 
 #[derive(Template)]
@@ -12,23 +12,43 @@ use super::{template_write, Page};
 struct TemplateArtists<'a> {
     page: Page,
     data: &'a Data,
-    artists: Vec<&'a Artist>,
+    count: usize,
+    nav: Vec<Nav>,
+    /// Empty on the index when there are letter pages.
+    letters: Vec<Letter<Artist>>,
 }
 
+/// The index of every artist by name, with a page per letter when it gets long.
 pub fn build_artists(path: &str, data: &Data) -> Result<(), Error> {
-    let path = path.to_owned() + "index.html";
-    let page = Page {
+    let artists = sort_artists(data.artists.values().cloned().collect());
+    let count = artists.len();
+    let paged = count > LETTER_PAGES_FROM;
+    let letters = group_by_letter(artists, |artist| &artist.name);
+
+    let path_index = path.to_owned() + "index.html";
+    let nav_index = nav(&letters, paged, None);
+    if !paged {
+        let index = TemplateArtists { page: page(), data, count, nav: nav_index, letters };
+        return template_write(&index.render().unwrap(), &path_index);
+    }
+    let index = TemplateArtists { page: page(), data, count, nav: nav_index, letters: vec![] };
+    template_write(&index.render().unwrap(), &path_index)?;
+
+    let navs: Vec<Vec<Nav>> = letters.iter().map(|letter| nav(&letters, true, Some(letter.label))).collect();
+    for (letter, nav) in letters.into_iter().zip(navs) {
+        let file = path.to_owned() + &letter.file() + ".html";
+        let template = TemplateArtists { page: page(), data, count, nav, letters: vec![letter] };
+        template_write(&template.render().unwrap(), &file)?;
+    }
+    Ok(())
+}
+
+fn page() -> Page {
+    Page {
         title: Some(String::from("Artists")),
         id_artist: None,
         id_album: None,
         id_track: None,
         meta: None,
-    };
-    let template = TemplateArtists {
-        page,
-        data,
-        artists: data.artists.values().collect(),
-    };
-    let content = template.render().unwrap();
-    template_write(&content, &path)
-}
\ No newline at end of file
+    }
+}
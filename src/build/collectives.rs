@@ -0,0 +1,34 @@
+use std::io::Error;
+
+use askama::Template;
+
+use crate::{types::music::Artist, yaml::Data};
+
+use super::{template_write, Page};
+
+
+#[derive(Template)]
+#[template(path = "collectives.html")]
+struct TemplateCollectives<'a> {
+    page: Page,
+    data: &'a Data,
+    collectives: Vec<Artist>,
+}
+
+pub fn build_collectives(path: &str, data: &Data) -> Result<(), Error> {
+    let path = path.to_owned() + "index.html";
+    let page = Page {
+        title: Some(String::from("Collectives")),
+        id_artist: None,
+        id_album: None,
+        id_track: None,
+        meta: None,
+    };
+    let template = TemplateCollectives {
+        page,
+        data,
+        collectives: data.get_collectives_sorted(),
+    };
+    let content = template.render().unwrap();
+    template_write(&content, &path)
+}
@@ -2,7 +2,7 @@ use std::io::Error;
 
 use askama::Template;
 
-use crate::yaml::Data;
+use crate::{yaml::Data, todo::Todo};
 
 use super::{Page, template_write};
 
@@ -11,6 +11,7 @@ use super::{Page, template_write};
 struct TemplateContributing<'a> {
     page: Page,
     data: &'a Data,
+    todo: Todo,
 }
 
 pub fn build_contribute(path: &str, data: &Data) -> Result<(), Error> {
@@ -22,7 +23,8 @@ pub fn build_contribute(path: &str, data: &Data) -> Result<(), Error> {
         id_track: None,
         meta: None,
     };
-    let template = TemplateContributing { page, data };
+    let todo = Todo::new(data);
+    let template = TemplateContributing { page, data, todo };
     let content = template.render().unwrap();
     template_write(&content, &path)
 }
@@ -1,10 +1,20 @@
-use std::{io::Error, collections::HashMap};
+use std::{io::{Error, ErrorKind}, collections::BTreeMap, fs::{create_dir_all, copy}, path::Path};
 
 use askama::Template;
 
-use crate::{yaml::{Data, Country}, types::music::Artist};
+use itertools::Itertools;
 
-use super::{template_write, Page};
+use crate::{yaml::{Data, Country}, types::music::Artist, map::{countries_to_svg, read_outlines}, collation_key};
+
+use super::{template_write, Page, Meta, META_TYPE_WEBSITE};
+
+/// Flags downloaded with `make flags`, they are copied to the output when present.
+const PATH_FLAGS: &str = "./lib/flags/";
+/// Country shapes downloaded with `make world`. Release builds fail without them, debug
+/// builds draw a tile map instead so the site builds offline.
+const PATH_WORLD: &str = "./lib/world.geojson";
+/// How many collectives are listed on a country page.
+const TOP_COLLECTIVES: usize = 5;
 
 #[derive(Template)]
 #[template(path = "countries.html")]
@@ -12,38 +22,120 @@ struct TemplateCountries<'a> {
     page: Page,
     data: &'a Data,
     countries: Vec<&'a Country>,
-    artists: &'a HashMap<String, Vec<Artist>>,
+    artists: &'a BTreeMap<String, Vec<Artist>>,
+    map: String,
 }
 
 pub fn build_countries(path: &str, data: &Data) -> Result<(), Error>  {
     let path_index = path.to_owned() + "index.html";
-    let countries: Vec<&Country> = data.countries.values().collect();
+    let countries: Vec<&Country> = data.countries.values().sorted_by_key(|country| collation_key(&country.name)).collect();
     let artists = &data.get_artists_by_country();
+    let outlines = read_outlines(PATH_WORLD)?;
+    if outlines.is_none() && !cfg!(debug_assertions) {
+        return Err(Error::new(ErrorKind::NotFound, format!("{} is missing, run `make world`", PATH_WORLD)));
+    }
+    let map = countries_to_svg(&data.countries, artists, outlines.as_ref());
+    template_write(&map, &(path.to_owned() + "map.svg"))?;
     let page = Page { title: Some(String::from("Countries")), id_artist: None, id_album: None, id_track: None, meta: None };
-    let template = TemplateCountries { page, data, countries, artists, };
+    let template = TemplateCountries { page, data, countries, artists, map };
     let content = template.render().unwrap();
     template_write(&content, &path_index)?;
 
     for (code, artists) in artists {
         let country = data.countries.get(code).unwrap();
-        build_country(&path, &data, country, artists)?;
+        build_country(path, data, country, artists)?;
     }
     Ok(())
 }
 
+/// Artists are counted without collectives, as the page lists them apart.
+struct CountryStats {
+    artists: usize,
+    collectives: usize,
+    albums: usize,
+    tracks: usize,
+}
+
 #[derive(Template)]
 #[template(path = "country.html")]
 struct TemplateCountry<'a> {
     page: Page,
     data: &'a Data,
     country: &'a Country,
-    artists: &'a Vec<Artist>,
+    flag: String,
+    stats: CountryStats,
+    artists: Vec<&'a Artist>,
+    /// Collective ids with their member count from this country.
+    collectives: Vec<(String, usize)>,
 }
 
-fn build_country(path: &str, data: &Data, country: &Country, artists: &Vec<Artist>) -> Result<(), Error>{
+fn build_country(path: &str, data: &Data, country: &Country, artists: &[Artist]) -> Result<(), Error>{
+    let flag = mirror_flag(path, country)?;
     let path = path.to_owned() + &country.code + ".html";
-    let page = Page { title: None, id_artist: None, id_album: None, id_track: None, meta: None };
-    let template = TemplateCountry { page, data, country, artists };
+
+    let stats = get_stats(data, artists);
+    let collectives = get_top_collectives(data, &country.code);
+    let mut artists: Vec<&Artist> = artists
+        .iter()
+        .filter(|artist| artist.collective_members.is_none())
+        .collect();
+    artists.sort_by_key(|artist| artist.name.to_lowercase());
+
+    let title = country.name.to_owned() + " " + &country.emoji;
+    let collectives_count = if stats.collectives > 0 { format!("{} collectives, ", stats.collectives) } else { String::new() };
+    let description = format!("{} artists, {}{} albums and {} tracks from {}", stats.artists, collectives_count, stats.albums, stats.tracks, country.name);
+    let meta = Some(Meta { title: title.clone(), url: path.clone(), r#type: META_TYPE_WEBSITE.to_owned(), image: flag.clone(), description });
+    let page = Page { title: Some(title), id_artist: None, id_album: None, id_track: None, meta };
+    let template = TemplateCountry { page, data, country, flag, stats, artists, collectives };
     let content = template.render().unwrap();
     template_write(&content, &path)
-} 
\ No newline at end of file
+}
+
+fn get_stats(data: &Data, artists: &[Artist]) -> CountryStats {
+    let albums: Vec<String> = artists
+        .iter()
+        .flat_map(|artist| data.get_albums_by(&artist.id))
+        .map(|album| album.key())
+        .collect();
+    let tracks = albums
+        .iter()
+        .map(|key_album| data.get_tracks_in_album(key_album).len())
+        .sum();
+    let collectives = artists.iter().filter(|artist| artist.collective_members.is_some()).count();
+    CountryStats { artists: artists.len() - collectives, collectives, albums: albums.len(), tracks }
+}
+
+/// Collectives ordered by how many of their members are from the country.
+fn get_top_collectives(data: &Data, code: &str) -> Vec<(String, usize)> {
+    let is_from_country = |id: &str| data.artists
+        .get(id)
+        .and_then(|artist| artist.country_code.as_ref())
+        .is_some_and(|country_code| country_code == code);
+
+    let mut collectives: Vec<(String, usize)> = data.get_collectives_sorted()
+        .iter()
+        .map(|collective| {
+            let members = collective.collective_members.as_ref().unwrap();
+            let count = members.iter().filter(|member| is_from_country(&member.id)).count();
+            (collective.id.clone(), count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    collectives.sort_by(|(_, a), (_, b)| b.cmp(a));
+    collectives.truncate(TOP_COLLECTIVES);
+    collectives
+}
+
+/// Copies the flag of a country into the output and returns where to find it.
+/// Falls back to the remote image when the flag was not downloaded.
+fn mirror_flag(path: &str, country: &Country) -> Result<String, Error> {
+    let file = country.code.to_owned() + ".svg";
+    let path_local = PATH_FLAGS.to_owned() + &file;
+    if !Path::new(&path_local).exists() { return Ok(country.image.clone()); }
+
+    let path_flags = path.to_owned() + "flags/";
+    create_dir_all(&path_flags)?;
+    let path_flag = path_flags + &file;
+    copy(&path_local, &path_flag)?;
+    Ok(path_flag)
+}
@@ -0,0 +1,13 @@
+use std::io::Error;
+
+use crate::{yaml::Data, graph::Graph};
+
+use super::template_write;
+
+/// Writes the artist graph as JSON, GraphML and DOT.
+pub fn build_graph(path: &str, data: &Data) -> Result<(), Error> {
+    let graph = Graph::new(data);
+    template_write(&graph.to_json(), &(path.to_owned() + "graph.json"))?;
+    template_write(&graph.to_graphml(), &(path.to_owned() + "graph.graphml"))?;
+    template_write(&graph.to_dot(), &(path.to_owned() + "graph.dot"))
+}
@@ -0,0 +1,59 @@
+use std::collections::BTreeMap;
+
+use crate::index_letter;
+
+/// Lists with more entries than this get a page per letter instead of one long page.
+pub const LETTER_PAGES_FROM: usize = 50;
+
+/// Entries of an index that start with the same letter.
+pub struct Letter<T> {
+    /// `A` to `Z`, or `#` for names that start with anything else.
+    pub label: char,
+    pub entries: Vec<T>,
+}
+
+impl<T> Letter<T> {
+    /// Name of the letter page without `.html`, `0` stands in for `#`.
+    pub fn file(&self) -> String {
+        file(self.label)
+    }
+
+    pub fn anchor(&self) -> String {
+        format!("letter-{}", self.file())
+    }
+}
+
+/// A letter in the bar above an index, without `href` when nothing starts with it.
+pub struct Nav {
+    pub label: char,
+    pub href: Option<String>,
+    pub current: bool,
+}
+
+fn file(label: char) -> String {
+    if label == '#' { String::from("0") } else { label.to_ascii_lowercase().to_string() }
+}
+
+/// Groups sorted entries by their letter, `#` first. Entries keep their order.
+pub fn group_by_letter<T>(entries: Vec<T>, name: impl Fn(&T) -> &str) -> Vec<Letter<T>> {
+    let mut letters: BTreeMap<char, Vec<T>> = BTreeMap::new();
+    for entry in entries {
+        letters.entry(index_letter(name(&entry))).or_default().push(entry);
+    }
+    letters.into_iter().map(|(label, entries)| Letter { label, entries }).collect()
+}
+
+/// `#` and `A` to `Z`. They link to the letter pages when `paged`, else to the
+/// anchors of the one page. `current` is the letter of the page shown.
+pub fn nav<T>(letters: &[Letter<T>], paged: bool, current: Option<char>) -> Vec<Nav> {
+    std::iter::once('#')
+        .chain('A'..='Z')
+        .map(|label| {
+            let letter = letters.iter().find(|letter| letter.label == label);
+            let href = letter.map(|letter| {
+                if paged { format!("{}.html", letter.file()) } else { format!("#{}", letter.anchor()) }
+            });
+            Nav { label, href, current: current == Some(label) }
+        })
+        .collect()
+}
@@ -7,24 +7,42 @@ use self::index::build_index;
 use self::contribute::build_contribute;
 use self::query::build_query;
 use self::artists::build_artists;
+use self::tracks::build_tracks;
+use self::years::build_years;
+use self::collectives::build_collectives;
 use self::artist::build_artist;
 use self::album::build_album;
 use self::track::build_track;
 use self::countries::build_countries;
+use self::graph::build_graph;
+use self::stats::build_stats;
+use self::playlists::build_playlists;
+use self::verify::verify;
 
 mod index;
 mod contribute;
 mod artists;
+mod tracks;
+mod letters;
+mod years;
+mod collectives;
 mod artist;
 mod countries;
 mod album;
 mod track;
 mod query;
+mod graph;
+mod stats;
+mod playlist;
+mod playlists;
+mod verify;
 
 pub fn build(path: &str, data: &Data) -> Result<(), Error> {
     build_index(&path, &data)?;
     build_contribute(&path, &data)?;
     build_query(&path, &data)?;
+    build_graph(&path, &data)?;
+    build_stats(&path, &data)?;
 
     let path_artists = path.to_owned() + "artists/";
     create_dir_all(&path_artists)?;
@@ -33,6 +51,14 @@ pub fn build(path: &str, data: &Data) -> Result<(), Error> {
         build_artist(&path_artists, &data, id_artist)?;
     }
 
+    let path_collectives = path.to_owned() + "collectives/";
+    create_dir_all(&path_collectives)?;
+    build_collectives(&path_collectives, &data)?;
+
+    build_tracks(&(path.to_owned() + "tracks/"), data)?;
+    build_years(&(path.to_owned() + "years/"), data)?;
+    build_playlists(&(path.to_owned() + "playlists/"), &data)?;
+
     let path_countries = path.to_owned() + "countries/";
     create_dir_all(&path_countries)?;
     build_countries(&path_countries, &data)?;
@@ -43,12 +69,12 @@ pub fn build(path: &str, data: &Data) -> Result<(), Error> {
     }
 
     for (id_track, track) in &data.tracks {
-        let album = data.get_album(&track.album_id);
+        let album = data.get_album(&track.album_key());
         let path_track = path_artists.to_owned() + &album.artist_id + "/" + &track.album_id + "/";
         build_track(&path_track, &data, &track)?;
     }
 
-    Ok(())
+    verify(path)
 }
 
 pub fn template_write(content: &str, path: &str) -> Result<(), Error> {
@@ -73,4 +99,5 @@ pub struct Meta {
 }
 
 pub const META_TYPE_SONG: &str = "music.song";
-pub const META_TYPE_ALBUM: &str = "music.album";
\ No newline at end of file
+pub const META_TYPE_ALBUM: &str = "music.album";
+pub const META_TYPE_WEBSITE: &str = "website";
\ No newline at end of file
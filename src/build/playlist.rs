@@ -0,0 +1,15 @@
+use std::io::Error;
+
+use crate::{yaml::Data, types::music::Track, playlist::{entries, to_format, FORMATS}};
+
+use super::template_write;
+
+/// Writes `playlist.m3u8`, `playlist.xspf` and `playlist.jspf` of the tracks into a page directory.
+pub fn build_playlist_files(path: &str, data: &Data, title: &str, tracks: &[Track]) -> Result<(), Error> {
+    let entries = entries(data, tracks);
+    for format in FORMATS {
+        let content = to_format(format, title, &entries).unwrap();
+        template_write(&content, &(path.to_owned() + "playlist." + format))?;
+    }
+    Ok(())
+}
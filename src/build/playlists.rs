@@ -0,0 +1,58 @@
+use std::{io::Error, fs::create_dir_all};
+
+use askama::Template;
+use chrono::Duration;
+use itertools::Itertools;
+
+use crate::{yaml::Data, types::music::Playlist, collation_key};
+
+use super::{template_write, Page, Meta, META_TYPE_WEBSITE, playlist::build_playlist_files};
+
+#[derive(Template)]
+#[template(path = "playlists.html")]
+struct TemplatePlaylists<'a> {
+    page: Page,
+    data: &'a Data,
+    playlists: Vec<&'a Playlist>,
+}
+
+#[derive(Template)]
+#[template(path = "playlist.html")]
+struct TemplatePlaylist<'a> {
+    page: Page,
+    data: &'a Data,
+    playlist: &'a Playlist,
+    duration: Duration,
+}
+
+pub fn build_playlists(path: &str, data: &Data) -> Result<(), Error> {
+    create_dir_all(path)?;
+    let playlists = data.playlists.values().sorted_by_key(|playlist| (collation_key(&playlist.name), &playlist.id)).collect();
+    let page = Page { title: Some(String::from("Playlists")), id_artist: None, id_album: None, id_track: None, meta: None };
+    let template = TemplatePlaylists { page, data, playlists };
+    let content = template.render().unwrap();
+    template_write(&content, &(path.to_owned() + "index.html"))?;
+
+    for playlist in data.playlists.values() {
+        build_playlist(path, data, playlist)?;
+    }
+    Ok(())
+}
+
+fn build_playlist(path: &str, data: &Data, playlist: &Playlist) -> Result<(), Error> {
+    let path_playlist = path.to_owned() + &playlist.id + "/";
+    create_dir_all(&path_playlist)?;
+    build_playlist_files(&path_playlist, data, &playlist.name, &playlist.tracks)?;
+
+    let duration = playlist.tracks.iter().filter_map(|track| track.duration).fold(Duration::zero(), |sum, duration| sum + duration);
+    let image = playlist.tracks
+        .first()
+        .map(|track| data.get_album(&track.album_key()).cover_url)
+        .unwrap_or_default();
+    let description = playlist.description.clone().unwrap_or_else(|| format!("{} tracks", playlist.tracks.len()));
+    let meta = Some(Meta { title: playlist.name.to_owned(), url: path_playlist.to_owned(), r#type: META_TYPE_WEBSITE.to_owned(), image, description });
+    let page = Page { title: Some(playlist.name.to_owned()), id_artist: None, id_album: None, id_track: None, meta };
+    let template = TemplatePlaylist { page, data, playlist, duration };
+    let content = template.render().unwrap();
+    template_write(&content, &(path_playlist + "index.html"))
+}
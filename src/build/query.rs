@@ -3,7 +3,7 @@ use std::io::Error;
 use askama::Template;
 use serde_json::{json, Value};
 
-use crate::{yaml::Data, types::music::{Artist, Album, Track}};
+use crate::{yaml::Data, types::music::{Artist, Album, Track}, playlist::best_url};
 
 use super::{template_write, Page};
 
@@ -21,7 +21,7 @@ pub fn build_query(path: &str, data: &Data) -> Result<(), Error> {
     let json_val = json!({
         "artists": data.artists.values().collect::<Vec<&Artist>>(),
         "albums": data.albums.values().collect::<Vec<&Album>>(),
-        "tracks": data.tracks.values().collect::<Vec<&Track>>(),
+        "tracks": data.tracks.values().map(track_json).collect::<Vec<Value>>(),
     });
     let json = serde_json::to_string(&json_val).unwrap();
 
@@ -38,4 +38,11 @@ pub fn build_query(path: &str, data: &Data) -> Result<(), Error> {
     template_write(&content, &path)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// A track with the url playlists exported by the page link to.
+fn track_json(track: &Track) -> Value {
+    let mut value = serde_json::to_value(track).unwrap();
+    value["url_best"] = json!(best_url(track));
+    value
+}
@@ -0,0 +1,30 @@
+use std::io::Error;
+
+use askama::Template;
+
+use crate::{yaml::Data, stats::Stats};
+
+use super::{template_write, Page};
+
+#[derive(Template)]
+#[template(path = "stats.html")]
+struct TemplateStats<'a> {
+    page: Page,
+    data: &'a Data,
+    stats: Stats,
+}
+
+pub fn build_stats(path: &str, data: &Data) -> Result<(), Error> {
+    let path = path.to_owned() + "stats.html";
+    let page = Page {
+        title: Some(String::from("Stats")),
+        id_artist: None,
+        id_album: None,
+        id_track: None,
+        meta: None,
+    };
+    let stats = Stats::new(data);
+    let template = TemplateStats { page, data, stats };
+    let content = template.render().unwrap();
+    template_write(&content, &path)
+}
@@ -2,7 +2,7 @@ use std::{io::{Error}, fs::create_dir_all, collections::HashMap};
 
 use askama::Template;
 
-use crate::{yaml::Data, types::music::{Album, Track, Artist, Sample}};
+use crate::{yaml::Data, types::music::{Album, Track, Artist, Sample}, wave::wave_to_svg};
 
 use super::{template_write, Page, Meta, META_TYPE_SONG};
 
@@ -18,10 +18,14 @@ struct TemplateTrack<'a> {
 
 
 pub fn build_track(path: &str, data: &Data, track: &Track) -> Result<(), Error> {
+    if let (Some(wave), Some(_)) = (&track.wave, track.duration) {
+        let path_wave = path.to_owned() + &track.id + ".svg";
+        template_write(&wave_to_svg(track, wave, &data.sample_types), &path_wave)?;
+    }
     let path = path.to_owned() + &track.id + ".html";
 
 
-    let album = data.get_album(track.album_id.as_str());
+    let album = data.get_album(&track.album_key());
     let id_artist = &album.artist_id;
     let artist_name = if let Some(artist) = data.get_artist(id_artist) {
         artist.name
@@ -0,0 +1,57 @@
+use std::{io::Error, fs::create_dir_all};
+
+use askama::Template;
+use itertools::Itertools;
+
+use crate::{types::music::Track, yaml::Data, collation_key};
+
+use super::{template_write, Page, letters::{group_by_letter, nav, Letter, Nav, LETTER_PAGES_FROM}};
+
+#[derive(Template)]
+#[template(path = "track_index.html")]
+struct TemplateTracks<'a> {
+    page: Page,
+    data: &'a Data,
+    count: usize,
+    nav: Vec<Nav>,
+    /// Empty on the index when there are letter pages.
+    letters: Vec<Letter<&'a Track>>,
+}
+
+/// The index of every track by name, with a page per letter when it gets long.
+pub fn build_tracks(path: &str, data: &Data) -> Result<(), Error> {
+    create_dir_all(path)?;
+    let tracks: Vec<&Track> = data.tracks.values()
+        .sorted_by_key(|track| (collation_key(&track.name), &track.artist_id, &track.album_id, &track.id))
+        .collect();
+    let count = tracks.len();
+    let paged = count > LETTER_PAGES_FROM;
+    let letters = group_by_letter(tracks, |track| &track.name);
+
+    let path_index = path.to_owned() + "index.html";
+    let nav_index = nav(&letters, paged, None);
+    if !paged {
+        let index = TemplateTracks { page: page(), data, count, nav: nav_index, letters };
+        return template_write(&index.render().unwrap(), &path_index);
+    }
+    let index = TemplateTracks { page: page(), data, count, nav: nav_index, letters: vec![] };
+    template_write(&index.render().unwrap(), &path_index)?;
+
+    let navs: Vec<Vec<Nav>> = letters.iter().map(|letter| nav(&letters, true, Some(letter.label))).collect();
+    for (letter, nav) in letters.into_iter().zip(navs) {
+        let file = path.to_owned() + &letter.file() + ".html";
+        let template = TemplateTracks { page: page(), data, count, nav, letters: vec![letter] };
+        template_write(&template.render().unwrap(), &file)?;
+    }
+    Ok(())
+}
+
+fn page() -> Page {
+    Page {
+        title: Some(String::from("Tracks")),
+        id_artist: None,
+        id_album: None,
+        id_track: None,
+        meta: None,
+    }
+}
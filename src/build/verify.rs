@@ -0,0 +1,124 @@
+use std::{fs::{read_dir, read_to_string}, io::{Error, ErrorKind}, path::{Component, Path, PathBuf}};
+
+use crate::{URL_SITE, URL_LOCAL};
+
+/// A link of a page that leads to no file of the output.
+pub struct Broken {
+    pub page: String,
+    pub link: String,
+}
+
+/// Checks every `href` and `src` of the pages in the output, fails with the broken ones.
+pub fn verify(path: &str) -> Result<(), Error> {
+    let broken = broken_links(path)?;
+    if broken.is_empty() { return Ok(()); }
+    let list: Vec<String> = broken.iter().map(|broken| format!("    {}: {}", broken.page, broken.link)).collect();
+    Err(Error::new(ErrorKind::InvalidData, format!("{} broken links:\n{}", broken.len(), list.join("\n"))))
+}
+
+/// Links of the pages under `path` that do not resolve to a file there, in page order.
+pub fn broken_links(path: &str) -> Result<Vec<Broken>, Error> {
+    let root = Path::new(path);
+    let mut pages = vec![];
+    find_pages(root, &mut pages)?;
+    pages.sort();
+
+    let mut broken = vec![];
+    for page in &pages {
+        let html = read_to_string(page)?;
+        let dir = page.parent().unwrap();
+        for link in links(&html) {
+            let Some(target) = resolve(root, dir, &link) else { continue };
+            if !exists(root, &target) {
+                broken.push(Broken { page: page.display().to_string(), link });
+            }
+        }
+    }
+    Ok(broken)
+}
+
+fn find_pages(dir: &Path, pages: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_pages(&path, pages)?;
+        } else if path.extension().is_some_and(|extension| extension == "html") {
+            pages.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Values of the `href` and `src` attributes, scripts left out as they build their own links.
+fn links(html: &str) -> Vec<String> {
+    let mut links = vec![];
+    for part in without_scripts(html) {
+        for attribute in [" href=", " src=", "\nhref=", "\nsrc="] {
+            for (start, _) in part.match_indices(attribute) {
+                let rest = &part[start + attribute.len()..];
+                let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else { continue };
+                let Some((value, _)) = rest[1..].split_once(quote) else { continue };
+                links.push(value.trim().to_owned());
+            }
+        }
+    }
+    links
+}
+
+fn without_scripts(html: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        parts.push(&rest[..start]);
+        rest = match rest[start..].find("</script>") {
+            Some(end) => &rest[start + end..],
+            None => "",
+        };
+    }
+    parts.push(rest);
+    parts
+}
+
+/// Path of a link relative to the output, `None` for links to other sites and to the page itself.
+fn resolve(root: &Path, dir: &Path, link: &str) -> Option<PathBuf> {
+    let link = link.split(['#', '?']).next().unwrap();
+    if link.is_empty() { return None; }
+    let (base, rest) = if let Some(rest) = [URL_SITE, URL_LOCAL].iter().find_map(|url| link.strip_prefix(url)) {
+        (root, rest)
+    } else if let Some(rest) = link.strip_prefix('/') {
+        if rest.starts_with('/') { return None; }
+        (root, rest)
+    } else if link.split('/').next().unwrap().contains(':') {
+        return None;
+    } else {
+        (dir, link)
+    };
+
+    let mut path = base.strip_prefix(root).unwrap().to_path_buf();
+    for component in Path::new(&percent_decode(rest)).components() {
+        // Above the output, which is never part of the site.
+        if component == Component::ParentDir && !path.pop() { return Some(PathBuf::from("..")); }
+        if let Component::Normal(name) = component { path.push(name); }
+    }
+    Some(path)
+}
+
+/// Links to folders lead to their `index.html`.
+fn exists(root: &Path, target: &Path) -> bool {
+    let path = root.join(target);
+    path.is_file() || path.join("index.html").is_file()
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => { out.push(byte); i += 3; }
+            (byte, _) => { out.push(byte); i += 1; }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
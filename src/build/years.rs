@@ -0,0 +1,47 @@
+use std::{io::Error, fs::create_dir_all};
+
+use askama::Template;
+
+use crate::{yaml::Data, types::music::Album};
+
+use super::{template_write, Page};
+
+#[derive(Template)]
+#[template(path = "years.html")]
+struct TemplateYears<'a> {
+    page: Page,
+    data: &'a Data,
+    /// Page name and album count of each year.
+    years: Vec<(String, usize)>,
+}
+
+#[derive(Template)]
+#[template(path = "year.html")]
+struct TemplateYear<'a> {
+    page: Page,
+    data: &'a Data,
+    albums: &'a [Album],
+}
+
+/// A page per release year, undated albums go on `unknown.html`.
+pub fn build_years(path: &str, data: &Data) -> Result<(), Error> {
+    create_dir_all(path)?;
+    let albums_by_year = data.get_albums_by_year();
+    let name = |year: &Option<i32>| year.map(|year| year.to_string()).unwrap_or_else(|| String::from("unknown"));
+
+    let years = albums_by_year.iter().map(|(year, albums)| (name(year), albums.len())).collect();
+    let page = Page { title: Some(String::from("Years")), id_artist: None, id_album: None, id_track: None, meta: None };
+    let template = TemplateYears { page, data, years };
+    template_write(&template.render().unwrap(), &(path.to_owned() + "index.html"))?;
+
+    for (year, albums) in &albums_by_year {
+        let title = match year {
+            Some(year) => format!("Released in {}", year),
+            None => String::from("Release date unknown"),
+        };
+        let page = Page { title: Some(title), id_artist: None, id_album: None, id_track: None, meta: None };
+        let template = TemplateYear { page, data, albums };
+        template_write(&template.render().unwrap(), &format!("{}{}.html", path, name(year)))?;
+    }
+    Ok(())
+}
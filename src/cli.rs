@@ -0,0 +1,75 @@
+use std::io::{stdin, stdout, Error, ErrorKind, IsTerminal, Write};
+
+/// Arguments after a subcommand: `--key value` flags, which may repeat, and positional values.
+pub struct Args {
+    pub positional: Vec<String>,
+    flags: Vec<(String, String)>,
+}
+
+impl Args {
+    /// `switches` are flags that take no value, like `--check`.
+    pub fn parse(args: &[String], switches: &[&str]) -> Args {
+        let mut positional = vec![];
+        let mut flags = vec![];
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+            if let Some((key, value)) = key.split_once('=') {
+                flags.push((key.to_owned(), value.to_owned()));
+            } else if switches.contains(&key) {
+                flags.push((key.to_owned(), String::new()));
+            } else {
+                let value = iter.next().cloned().unwrap_or_default();
+                flags.push((key.to_owned(), value));
+            }
+        }
+        Args { positional, flags }
+    }
+
+    pub fn has(&self, key: &str) -> bool {
+        self.flags.iter().any(|(k, _)| k == key)
+    }
+
+    /// Last value given for a flag.
+    pub fn get(&self, key: &str) -> Option<String> {
+        self.flags.iter().rev().find(|(k, _)| k == key).map(|(_, value)| value.clone())
+    }
+
+    pub fn get_all(&self, key: &str) -> Vec<String> {
+        self.flags.iter().filter(|(k, _)| k == key).map(|(_, value)| value.clone()).collect()
+    }
+
+    /// Value of a flag, asking for it on the terminal when missing.
+    /// Errors when a required value is neither given nor typed in.
+    pub fn get_or_prompt(&self, key: &str, required: bool) -> Result<Option<String>, Error> {
+        if let Some(value) = self.get(key) { return Ok(Some(value)); }
+        let value = if stdin().is_terminal() {
+            let label = if required { key.to_owned() } else { key.to_owned() + " (optional)" };
+            prompt(&label)?
+        } else { String::new() };
+
+        if !value.is_empty() { return Ok(Some(value)); }
+        if required { return Err(Error::new(ErrorKind::InvalidInput, format!("--{} is required", key))); }
+        Ok(None)
+    }
+
+    /// All values of a repeated flag, or a comma separated list typed in on the terminal.
+    pub fn get_all_or_prompt(&self, key: &str) -> Result<Vec<String>, Error> {
+        let values = self.get_all(key);
+        if !values.is_empty() || !stdin().is_terminal() { return Ok(values); }
+        let typed = prompt(&(key.to_owned() + "s (comma separated, optional)"))?;
+        Ok(typed.split(',').map(str::trim).filter(|value| !value.is_empty()).map(str::to_owned).collect())
+    }
+}
+
+/// Asks for a line on the terminal.
+pub fn prompt(label: &str) -> Result<String, Error> {
+    print!("{}: ", label);
+    stdout().flush()?;
+    let mut line = String::new();
+    stdin().read_line(&mut line)?;
+    Ok(line.trim().to_owned())
+}
@@ -0,0 +1,147 @@
+use std::{io::{Error, ErrorKind}, fs::{read_to_string, write}, cmp::Ordering, path::Path};
+
+use itertools::Itertools;
+use serde::{Serialize, de::DeserializeOwned};
+use serde_yaml::{Value, Mapping};
+
+use crate::{yaml::{YamlArtist, YamlAlbum, YamlTrack, YamlPlaylist, file_names}, is_duration, str_to_duration, duration_normalize};
+
+/// Rewrites every artist, album and playlist file of the db in the canonical layout and
+/// returns the paths of the files that changed. With `check` nothing is written.
+///
+/// The layout is what serde writes for the Yaml* structs with every map's keys
+/// sorted, tracks ordered by position, times as `m:ss` and urls sorted. Comments
+/// are not kept, keys the structs do not know are an error instead of being dropped.
+pub fn format_db(path_db: &str, check: bool) -> Result<Vec<String>, Error> {
+    let mut changed = vec![];
+    let path_artists = path_db.to_owned() + "artists/";
+    for id_artist in file_names(&path_artists)? {
+        let path_artist = path_artists.to_owned() + &id_artist + "/";
+        for file in file_names(&path_artist)? {
+            let path = path_artist.to_owned() + &file;
+            let is_changed = if file == ".artist.yml" {
+                format_file(&path, check, format_artist)?
+            } else if file.starts_with('.') {
+                continue;
+            } else {
+                format_file(&path, check, format_album)?
+            };
+            if is_changed { changed.push(path); }
+        }
+    }
+
+    let path_playlists = path_db.to_owned() + "playlists/";
+    if !Path::new(&path_playlists).exists() { return Ok(changed); }
+    for file in file_names(&path_playlists)? {
+        if !file.ends_with(".yml") { continue; }
+        let path = path_playlists.to_owned() + &file;
+        // The order of the tracks is the playlist, only the layout changes.
+        if format_file(&path, check, |_: &mut YamlPlaylist| {})? { changed.push(path); }
+    }
+    Ok(changed)
+}
+
+fn format_file<T: Serialize + DeserializeOwned>(path: &str, check: bool, format: fn(&mut T)) -> Result<bool, Error> {
+    let content = read_to_string(path)?;
+    let mut yaml: T = serde_yaml::from_str(&content)
+        .map_err(|error| Error::new(ErrorKind::InvalidData, format!("{}: {}", path, error)))?;
+    let formatted = to_canonical_yaml(&mut yaml, format);
+    if formatted == content { return Ok(false); }
+    if !check { write(path, formatted)?; }
+    Ok(true)
+}
+
+/// A file in the canonical layout after `format` is applied, as `site fmt` writes it.
+///
+/// ```
+/// use site::{fmt::{to_canonical_yaml, format_album}, yaml::YamlAlbum};
+///
+/// let file = "
+/// name: Drift
+/// genre: phonk
+/// released: 2021
+/// cover: cover.jpg
+/// track_count: 1
+/// tracks:
+///   1:
+///     name: Drift
+///     duration: 02:05
+///     location:
+///       - url: https://example.bandcamp.com/track/drift
+///         at: 0:00:07
+/// ";
+/// let mut album: YamlAlbum = serde_yaml::from_str(file).unwrap();
+/// let formatted = to_canonical_yaml(&mut album, format_album);
+/// let mut again: YamlAlbum = serde_yaml::from_str(&formatted).unwrap();
+/// assert_eq!(again.tracks["1"].duration.as_deref(), Some("2:05"));
+/// assert_eq!(again.tracks["1"].location[0].at.as_deref(), Some("0:07"));
+/// assert_eq!(to_canonical_yaml(&mut again, format_album), formatted);
+///
+/// // Unknown keys are an error at any depth instead of being dropped.
+/// let unknown = file.replace("        at: 0:00:07", "        at: 0:00:07\n        quality: 320");
+/// let error = serde_yaml::from_str::<YamlAlbum>(&unknown).unwrap_err();
+/// assert!(error.to_string().contains("unknown field `quality`"));
+/// ```
+pub fn to_canonical_yaml<T: Serialize>(yaml: &mut T, format: fn(&mut T)) -> String {
+    format(yaml);
+    let value = serde_yaml::to_value(&*yaml).unwrap();
+    serde_yaml::to_string(&sort_keys(value)).unwrap()
+}
+
+pub fn format_artist(yaml: &mut YamlArtist) {
+    sort_urls(&mut yaml.urls);
+}
+
+pub fn format_album(yaml: &mut YamlAlbum) {
+    if let Some(urls) = &mut yaml.urls { sort_urls(urls); }
+    for track in yaml.tracks.values_mut() { format_track(track); }
+}
+
+fn format_track(track: &mut YamlTrack) {
+    if let Some(duration) = &mut track.duration { normalize(duration); }
+    for location in &mut track.location {
+        if let Some(at) = &mut location.at { normalize(at); }
+    }
+    for sample in track.sample.iter_mut().flatten() {
+        for occurs in &mut sample.occurs {
+            normalize(&mut occurs.from);
+            normalize(&mut occurs.to);
+            normalize(&mut occurs.at);
+        }
+    }
+}
+
+/// Rewrites a time in the canonical format, anything else (like `end`) is left alone.
+fn normalize(time: &mut String) {
+    if !is_duration(time) { return; }
+    *time = duration_normalize(&str_to_duration(time));
+}
+
+fn sort_urls(urls: &mut Vec<String>) {
+    urls.sort();
+    urls.dedup();
+}
+
+/// Sorts the keys of every map, numbers (track positions) first and in order.
+fn sort_keys(value: Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .into_iter()
+                .sorted_by(|(a, _), (b, _)| compare_keys(a, b))
+                .map(|(key, value)| (key, sort_keys(value)))
+                .collect::<Mapping>(),
+        ),
+        Value::Sequence(values) => Value::Sequence(values.into_iter().map(sort_keys).collect()),
+        value => value,
+    }
+}
+
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()).unwrap_or(Ordering::Equal),
+        (Value::Number(_), _) => Ordering::Less,
+        (_, Value::Number(_)) => Ordering::Greater,
+        _ => a.as_str().cmp(&b.as_str()),
+    }
+}
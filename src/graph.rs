@@ -0,0 +1,153 @@
+use std::{collections::{BTreeMap, BTreeSet}, fmt::Write};
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::{yaml::Data, parse_name, xml_escape};
+
+/// How two artists are connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    /// Both artists are on the same track, undirected.
+    Collab,
+    /// The source is a member of the target collective.
+    Member,
+    /// The source sampled a track by the target.
+    Sample,
+}
+
+impl EdgeKind {
+    fn name(&self) -> &str {
+        match self {
+            EdgeKind::Collab => "collab",
+            EdgeKind::Member => "member",
+            EdgeKind::Sample => "sample",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Node {
+    pub id: String,
+    pub name: String,
+    pub is_collective: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Edge {
+    pub source: String,
+    pub target: String,
+    pub kind: EdgeKind,
+    /// Number of shared tracks, samples, or 1 for members.
+    pub weight: usize,
+}
+
+/// Every artist and how they are connected, sorted so exports are stable.
+#[derive(Debug, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+impl Graph {
+    pub fn new(data: &Data) -> Graph {
+        let mut weights: BTreeMap<(String, String, EdgeKind), usize> = BTreeMap::new();
+        let mut add = |source: &str, target: &str, kind: EdgeKind| {
+            if source == target { return; }
+            *weights.entry((source.to_owned(), target.to_owned(), kind)).or_insert(0) += 1;
+        };
+
+        for track in data.tracks.values() {
+            let ids: BTreeSet<&str> = track.artists.iter().map(|artist| artist.id.as_str()).collect();
+            for (a, b) in ids.iter().tuple_combinations() {
+                add(a, b, EdgeKind::Collab);
+            }
+            for sample in &track.samples {
+                let id_sampled = parse_name(&sample.artist);
+                if !data.artists.contains_key(&id_sampled) { continue; }
+                for id in &ids { add(id, &id_sampled, EdgeKind::Sample); }
+            }
+        }
+        for collective in data.artists.values() {
+            let Some(members) = &collective.collective_members else { continue };
+            for member in members { add(&member.id, &collective.id, EdgeKind::Member); }
+        }
+
+        let ids: BTreeSet<&String> = weights
+            .keys()
+            .flat_map(|(source, target, _)| [source, target])
+            .chain(data.artists.keys())
+            .collect();
+        let nodes = ids
+            .into_iter()
+            .map(|id| match data.artists.get(id) {
+                Some(artist) => Node { id: id.clone(), name: artist.name.clone(), is_collective: artist.collective_members.is_some() },
+                None => Node { id: id.clone(), name: id.clone(), is_collective: false },
+            })
+            .collect();
+        let edges = weights
+            .into_iter()
+            .map(|((source, target, kind), weight)| Edge { source, target, kind, weight })
+            .collect();
+        Graph { nodes, edges }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::new();
+        xml.push_str(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push('\n');
+        xml.push_str(r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#);
+        xml.push('\n');
+        xml.push_str(r#"  <key id="name" for="node" attr.name="name" attr.type="string"/>"#);
+        xml.push('\n');
+        xml.push_str(r#"  <key id="is_collective" for="node" attr.name="is_collective" attr.type="boolean"/>"#);
+        xml.push('\n');
+        xml.push_str(r#"  <key id="kind" for="edge" attr.name="kind" attr.type="string"/>"#);
+        xml.push('\n');
+        xml.push_str(r#"  <key id="weight" for="edge" attr.name="weight" attr.type="int"/>"#);
+        xml.push('\n');
+        xml.push_str(r#"  <graph id="phonkhub" edgedefault="directed">"#);
+        xml.push('\n');
+        for node in &self.nodes {
+            writeln!(
+                xml,
+                r#"    <node id="{}"><data key="name">{}</data><data key="is_collective">{}</data></node>"#,
+                xml_escape(&node.id), xml_escape(&node.name), node.is_collective,
+            ).unwrap();
+        }
+        for edge in &self.edges {
+            let directed = edge.kind != EdgeKind::Collab;
+            writeln!(
+                xml,
+                r#"    <edge source="{}" target="{}" directed="{}"><data key="kind">{}</data><data key="weight">{}</data></edge>"#,
+                xml_escape(&edge.source), xml_escape(&edge.target), directed, edge.kind.name(), edge.weight,
+            ).unwrap();
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    pub fn to_dot(&self) -> String {
+        let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = String::from("digraph phonkhub {\n");
+        for node in &self.nodes {
+            let shape = if node.is_collective { "box" } else { "ellipse" };
+            writeln!(dot, "    {} [label={}, shape={}];", quote(&node.id), quote(&node.name), shape).unwrap();
+        }
+        for edge in &self.edges {
+            let dir = if edge.kind == EdgeKind::Collab { "none" } else { "forward" };
+            writeln!(
+                dot,
+                "    {} -> {} [kind={}, weight={}, label={}, dir={}];",
+                quote(&edge.source), quote(&edge.target), edge.kind.name(), edge.weight, edge.weight, dir,
+            ).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
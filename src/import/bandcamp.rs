@@ -0,0 +1,180 @@
+use std::{io::Error, fs::read_to_string, collections::HashMap};
+
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+
+use crate::{types::music::TrackStatus, cli::Args, yaml::{YamlAlbum, YamlTrack, YamlTrackArtist, YamlLocation}, scaffold::{write_album, invalid}, parse_name, duration_normalize};
+
+use super::html_unescape;
+
+/// The part of Bandcamp's `TralbumData` that ends up in an album file.
+#[derive(Debug, Deserialize)]
+struct Tralbum {
+    artist: String,
+    url: String,
+    art_id: Option<u64>,
+    album_release_date: Option<String>,
+    current: TralbumCurrent,
+    trackinfo: Vec<TralbumTrack>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TralbumCurrent {
+    title: String,
+    release_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TralbumTrack {
+    title: String,
+    track_num: Option<u8>,
+    duration: Option<f64>,
+    title_link: Option<String>,
+    track_id: Option<u64>,
+    /// Only set when the track is by someone other than the album artist.
+    artist: Option<String>,
+}
+
+/// Writes an album file from a saved Bandcamp album or track page and returns its path.
+///
+/// `site import bandcamp smoked-out.html --artist dj-smokey --genre phonk`
+///
+/// The artist defaults to the id of the Bandcamp artist name and the genre to the first tag of the page.
+pub fn import_bandcamp(path_db: &str, args: &Args) -> Result<String, Error> {
+    let path = args.positional.first().ok_or_else(|| invalid("the path of a saved Bandcamp page is required".to_owned()))?;
+    let html = read_to_string(path)?;
+    let tralbum = read_tralbum(&html).map_err(|error| invalid(format!("{}: {}", path, error)))?;
+
+    let id_artist = args.get("artist").unwrap_or_else(|| parse_name(&tralbum.artist));
+    let genre = match args.get("genre").or_else(|| read_tags(&html).into_iter().next()) {
+        Some(genre) => genre,
+        None => args.get_or_prompt("genre", true)?.unwrap(),
+    };
+    let yaml = to_album(&tralbum, &id_artist, genre)?;
+    write_album(path_db, &id_artist, yaml)
+}
+
+/// Reads `TralbumData` from the `data-tralbum` attribute of current pages,
+/// or from the script variable of older ones.
+fn read_tralbum(html: &str) -> Result<Tralbum, String> {
+    let json = if let Some(start) = html.find("data-tralbum=\"") {
+        let rest = &html[start + "data-tralbum=\"".len()..];
+        let end = rest.find('"').ok_or("data-tralbum is not closed")?;
+        html_unescape(&rest[..end])
+    } else if let Some(start) = html.find("TralbumData = {") {
+        object_at(&html[start + "TralbumData = ".len()..]).ok_or("TralbumData is not closed")?.to_owned()
+    } else {
+        return Err("no TralbumData found, is this a Bandcamp album page?".to_owned());
+    };
+    serde_json::from_str(&json).map_err(|error| format!("TralbumData: {}", error))
+}
+
+/// The `{...}` object at the start of `text`, braces in strings are skipped.
+fn object_at(text: &str) -> Option<&str> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, char) in text.char_indices() {
+        if in_string {
+            match char {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match char {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 { return Some(&text[..=i]); }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Texts of the `<a class="tag">` links under an album.
+fn read_tags(html: &str) -> Vec<String> {
+    html.split("class=\"tag\"")
+        .skip(1)
+        .filter_map(|rest| {
+            let start = rest.find('>')? + 1;
+            let end = rest[start..].find('<')? + start;
+            Some(html_unescape(rest[start..end].trim()).to_lowercase())
+        })
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+fn to_album(tralbum: &Tralbum, id_artist: &str, genre: String) -> Result<YamlAlbum, Error> {
+    let released = tralbum.album_release_date
+        .as_ref()
+        .or(tralbum.current.release_date.as_ref())
+        .ok_or_else(|| invalid("the page has no release date".to_owned()))?;
+    let released = parse_date(released).ok_or_else(|| invalid(format!("unknown release date: {}", released)))?;
+    let art_id = tralbum.art_id.ok_or_else(|| invalid("the page has no cover".to_owned()))?;
+    let cover = format!("https://f4.bcbits.com/img/a{:010}_10.jpg", art_id);
+    let origin = origin(&tralbum.url);
+
+    let mut tracks = HashMap::new();
+    for (i, info) in tralbum.trackinfo.iter().enumerate() {
+        let position = info.track_num.map(usize::from).unwrap_or(i + 1);
+        // Tracks that are not out yet have no duration on the page.
+        let duration = info.duration
+            .filter(|seconds| *seconds > 0.0)
+            .map(|seconds| duration_normalize(&Duration::seconds(seconds.round() as i64)));
+        let artists = info.artist
+            .as_ref()
+            .map(|artist| parse_name(artist))
+            .filter(|id| !id.is_empty() && id != id_artist)
+            .map(|id| vec![YamlTrackArtist { id, r#for: None }]);
+        let location = info.title_link
+            .as_ref()
+            .map(|link| vec![YamlLocation { url: origin.to_owned() + link, at: None, id: info.track_id.map(|id| id.to_string()) }])
+            .unwrap_or_default();
+        tracks.insert(position.to_string(), YamlTrack {
+            name: info.title.to_owned(),
+            duration,
+            status: TrackStatus::Released,
+            artists,
+            artist: None,
+            location,
+            sample: None,
+            lyrics: None,
+            wave: None,
+        });
+    }
+    if tracks.is_empty() { return Err(invalid("the page has no tracks".to_owned())); }
+    let track_count = tracks.len().try_into().map_err(|_| invalid("too many tracks".to_owned()))?;
+
+    Ok(YamlAlbum {
+        name: tralbum.current.title.to_owned(),
+        artists: None,
+        genre,
+        released: released.into(),
+        r#type: None,
+        cover,
+        tracks,
+        track_count,
+        urls: Some(vec![tralbum.url.to_owned()]),
+    })
+}
+
+/// Bandcamp writes dates like `20 Apr 2016 00:00:00 GMT`.
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    let day = date.split_whitespace().take(3).collect::<Vec<_>>().join(" ");
+    NaiveDate::parse_from_str(&day, "%d %b %Y").ok()
+}
+
+/// `https://djsmokey.bandcamp.com` of `https://djsmokey.bandcamp.com/album/smoked-out`.
+fn origin(url: &str) -> &str {
+    let host_start = url.find("://").map(|i| i + 3).unwrap_or(0);
+    match url[host_start..].find('/') {
+        Some(i) => &url[..host_start + i],
+        None => url,
+    }
+}
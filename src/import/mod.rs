@@ -0,0 +1,48 @@
+pub mod bandcamp;
+pub mod tags;
+
+/// Decodes the character references left in HTML attributes and text.
+///
+/// ```
+/// use site::import::html_unescape;
+/// assert_eq!(html_unescape("{&quot;title&quot;:&quot;Smoked Out&quot;}"), r#"{"title":"Smoked Out"}"#);
+/// assert_eq!(html_unescape("Tom &amp; Jerry &#39;95 &#x2014; &lt;live&gt;"), "Tom & Jerry '95 \u{2014} <live>");
+/// // What is not a character reference is kept as it is.
+/// assert_eq!(html_unescape("R&B; &bogus; & more"), "R&B; &bogus; & more");
+/// ```
+pub fn html_unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else { break };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "quot" => Some('"'),
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "apos" => Some('\''),
+            "nbsp" => Some('\u{a0}'),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(char) => {
+                out.push(char);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,256 @@
+use std::{io::{Error, ErrorKind}, fs::{read_dir, File}, collections::HashMap, path::{Path, PathBuf}};
+
+use chrono::{Duration, NaiveDate};
+use itertools::Itertools;
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::DecoderOptions,
+    errors::Error as AudioError,
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::{MetadataOptions, StandardTagKey, Tag},
+    probe::Hint,
+};
+
+use crate::{types::{date::PartialDate, music::{Artist, TrackStatus}}, cli::Args, yaml::{YamlAlbum, YamlTrack, YamlTrackArtist, read_data, Data}, scaffold::{write_album, invalid}, wave::wave_from_peaks, parse_name, duration_normalize};
+
+const EXTENSIONS: [&str; 4] = ["mp3", "flac", "ogg", "oga"];
+
+/// Separators between the artists of one artist tag. Names of known artists that
+/// contain one, like `Tyler, The Creator`, are not split.
+const ARTIST_SEPARATORS: [&str; 8] = [", ", " & ", " feat. ", " ft. ", " featuring ", " x ", " X ", "; "];
+
+/// What one audio file says about itself.
+struct FileTags {
+    title: Option<String>,
+    position: Option<usize>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    artist: Option<String>,
+    date: Option<String>,
+    genre: Option<String>,
+    duration: Duration,
+    wave_peaks: Vec<f32>,
+}
+
+/// Writes an album file from the tags of the audio files in a folder and returns its path.
+///
+/// `site import tags ~/music/smoked-out --cover <url> [--wave]`
+///
+/// Flags override the tags: `--artist`, `--name`, `--genre`, `--released` and `--cover`,
+/// which has to be given as tags hold no cover url. With `--wave` the files are decoded
+/// to compute the wave of every track too.
+pub fn import_tags(path_db: &str, args: &Args) -> Result<String, Error> {
+    let folder = args.positional.first().ok_or_else(|| invalid("the path of a folder with audio files is required".to_owned()))?;
+    let with_wave = args.has("wave");
+    let files = audio_files(folder)?;
+    if files.is_empty() { return Err(invalid(format!("no {} files in {}", EXTENSIONS.join("/"), folder))); }
+    let files: Vec<FileTags> = files.iter().map(|path| read_file(path, with_wave)).collect::<Result<_, _>>()?;
+    let data = read_data(path_db)?;
+
+    let first = |get: fn(&FileTags) -> &Option<String>| files.iter().find_map(|file| get(file).clone());
+    let id_artist = match args.get("artist") {
+        Some(id) => id,
+        None => {
+            let name = first(|file| &file.album_artist)
+                .or_else(|| first(|file| &file.artist))
+                .ok_or_else(|| invalid("the files have no artist tag, use --artist".to_owned()))?;
+            artist_id(&data, split_artists(&data, &name).first().unwrap_or(&name))
+        }
+    };
+    let name = match args.get("name").or_else(|| first(|file| &file.album)) {
+        Some(name) => name,
+        None => args.get_or_prompt("name", true)?.unwrap(),
+    };
+    let genre = match args.get("genre").or_else(|| first(|file| &file.genre).map(|genre| genre.to_lowercase())) {
+        Some(genre) => genre,
+        None => args.get_or_prompt("genre", true)?.unwrap(),
+    };
+    let released = match args.get("released").or_else(|| first(|file| &file.date)).as_deref().and_then(parse_date) {
+        Some(released) => released,
+        None => {
+            let released = args.get_or_prompt("released", true)?.unwrap();
+            parse_date(&released).ok_or_else(|| invalid(format!("released has to be YYYY-MM-DD, YYYY-MM, YYYY, ~YYYY or unknown: {}", released)))?
+        }
+    };
+    let cover = args.get_or_prompt("cover", true)?.unwrap();
+
+    let mut tracks = HashMap::new();
+    for (i, file) in files.iter().enumerate() {
+        let position = file.position.unwrap_or(i + 1);
+        let title = file.title.clone().unwrap_or_else(|| format!("Track {}", position));
+        let ids: Vec<String> = file.artist
+            .iter()
+            .flat_map(|artist| split_artists(&data, artist))
+            .map(|name| artist_id(&data, &name))
+            .unique()
+            .collect();
+        let artists = if ids.is_empty() || ids == [id_artist.to_owned()] { None } else {
+            Some(ids.into_iter().map(|id| YamlTrackArtist { id, r#for: None }).collect())
+        };
+        let wave = if with_wave && !file.wave_peaks.is_empty() { Some(wave_from_peaks(&file.wave_peaks)) } else { None };
+        let track = YamlTrack {
+            name: title,
+            duration: Some(duration_normalize(&file.duration)),
+            status: TrackStatus::Released,
+            artists,
+            artist: None,
+            location: vec![],
+            sample: None,
+            lyrics: None,
+            wave,
+        };
+        if tracks.insert(position.to_string(), track).is_some() {
+            return Err(invalid(format!("two files are track {}", position)));
+        }
+    }
+    let track_count = tracks.len().try_into().map_err(|_| invalid("too many tracks".to_owned()))?;
+
+    let yaml = YamlAlbum { name, artists: None, genre, released, r#type: None, cover, tracks, track_count, urls: None };
+    write_album(path_db, &id_artist, yaml)
+}
+
+fn audio_files(folder: &str) -> Result<Vec<PathBuf>, Error> {
+    let mut files = vec![];
+    for entry in read_dir(folder)? {
+        let path = entry?.path();
+        let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default().to_lowercase();
+        if EXTENSIONS.contains(&extension.as_str()) { files.push(path); }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Reads the tags and the duration of a file, decoding it only when the wave is wanted
+/// or the container does not know its length.
+fn read_file(path: &Path, with_wave: bool) -> Result<FileTags, Error> {
+    let audio_error = |error: AudioError| Error::new(ErrorKind::InvalidData, format!("{}: {}", path.display(), error));
+
+    let source = MediaSourceStream::new(Box::new(File::open(path)?), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) { hint.with_extension(extension); }
+    let mut probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(audio_error)?;
+
+    // ID3 is read while probing, Vorbis comments belong to the container.
+    let mut tags: Vec<Tag> = vec![];
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|metadata| metadata.current()) {
+        tags.extend(revision.tags().iter().cloned());
+    }
+    let mut format = probed.format;
+    if let Some(revision) = format.metadata().current() {
+        tags.extend(revision.tags().iter().cloned());
+    }
+    let tag = |key: StandardTagKey| tags
+        .iter()
+        .find(|tag| tag.std_key == Some(key))
+        .map(|tag| tag.value.to_string().trim().to_owned())
+        .filter(|value| !value.is_empty());
+
+    let track = format.default_track().ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("{}: no audio track", path.display())))?;
+    let id_track = track.id;
+    let params = track.codec_params.clone();
+    let sample_rate = params.sample_rate.ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("{}: unknown sample rate", path.display())))?;
+
+    let mut frames = params.n_frames;
+    let mut wave_peaks = vec![];
+    if with_wave || frames.is_none() {
+        let mut decoder = symphonia::default::get_codecs().make(&params, &DecoderOptions::default()).map_err(audio_error)?;
+        let mut counted = 0;
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(AudioError::IoError(error)) if error.kind() == ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(audio_error(error)),
+            };
+            if packet.track_id() != id_track { continue; }
+            counted += packet.dur;
+            if !with_wave { continue; }
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(AudioError::DecodeError(_)) => continue,
+                Err(error) => return Err(audio_error(error)),
+            };
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+            buffer.copy_interleaved_ref(decoded);
+            wave_peaks.push(buffer.samples().iter().map(|sample| sample.abs()).fold(0.0, f32::max));
+        }
+        frames = frames.or(Some(counted));
+    }
+    let sample_rate = u64::from(sample_rate);
+    let duration = Duration::seconds(((frames.unwrap_or(0) + sample_rate / 2) / sample_rate) as i64);
+
+    Ok(FileTags {
+        title: tag(StandardTagKey::TrackTitle),
+        position: tag(StandardTagKey::TrackNumber).and_then(|number| number.split('/').next()?.trim().parse().ok()),
+        album: tag(StandardTagKey::Album),
+        album_artist: tag(StandardTagKey::AlbumArtist),
+        artist: tag(StandardTagKey::Artist),
+        date: tag(StandardTagKey::ReleaseDate).or_else(|| tag(StandardTagKey::Date)).or_else(|| tag(StandardTagKey::OriginalDate)),
+        genre: tag(StandardTagKey::Genre),
+        duration,
+        wave_peaks,
+    })
+}
+
+/// `DJ Smokey feat. MC Lean` is two artists. Names of known artists are kept whole,
+/// and where the tag can be cut in several places a cut next to a known artist wins,
+/// so `Mr. X X Y` is `Mr. X` and `Y` when Mr. X is known.
+fn split_artists(data: &Data, artists: &str) -> Vec<String> {
+    let artists = artists.trim();
+    if artists.is_empty() { return vec![]; }
+    if known_artist(data, artists).is_some() { return vec![artists.to_owned()]; }
+
+    let cuts: Vec<(&str, &str)> = ARTIST_SEPARATORS
+        .iter()
+        .flat_map(|separator| {
+            artists
+                .char_indices()
+                .filter(move |(i, _)| artists[*i..].starts_with(separator))
+                .map(move |(i, _)| (&artists[..i], &artists[i + separator.len()..]))
+        })
+        .collect();
+    let is_known = |part: &str| known_artist(data, part.trim()).is_some();
+    let cut = cuts.iter().find(|(left, right)| is_known(left) || is_known(right)).or(cuts.first());
+    match cut {
+        Some((left, right)) => [split_artists(data, left), split_artists(data, right)].concat(),
+        None => vec![artists.to_owned()],
+    }
+}
+
+/// Artist with that name, alias or name in another script.
+fn known_artist<'a>(data: &'a Data, name: &str) -> Option<&'a Artist> {
+    data.artists.values().find(|artist| {
+        std::iter::once(&artist.name)
+            .chain(&artist.aliases)
+            .chain(artist.names.values())
+            .any(|known| known.eq_ignore_ascii_case(name))
+    })
+}
+
+/// Id of a known artist with that name, or the id the name would get.
+fn artist_id(data: &Data, name: &str) -> String {
+    known_artist(data, name)
+        .map(|artist| artist.id.to_owned())
+        .unwrap_or_else(|| parse_name(name))
+}
+
+/// Tags hold `2016-04-20`, sometimes with a time after it, or just `2016`.
+///
+/// ```
+/// use site::{import::tags::parse_date, types::date::PartialDate};
+/// use chrono::NaiveDate;
+///
+/// let day = PartialDate::Day(NaiveDate::from_ymd_opt(2016, 4, 20).unwrap());
+/// assert_eq!(parse_date("2016-04-20"), Some(day));
+/// assert_eq!(parse_date("2016-04-20T00:00:00Z"), Some(day));
+/// assert_eq!(parse_date("2016-04-20 12:30"), Some(day));
+/// assert_eq!(parse_date("2016"), Some(PartialDate::Year(2016)));
+/// assert_eq!(parse_date("2016-04"), Some(PartialDate::Month(2016, 4)));
+/// assert_eq!(parse_date("April 2016"), None);
+/// ```
+pub fn parse_date(date: &str) -> Option<PartialDate> {
+    let day = date.get(..10).and_then(|day| NaiveDate::parse_from_str(day, "%Y-%m-%d").ok());
+    day.map(PartialDate::Day).or_else(|| PartialDate::parse(date))
+}
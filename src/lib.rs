@@ -1,34 +1,108 @@
 use std::{hash, ops::Mul};
 use chrono::Duration;
-use itertools::Itertools;
 use md5;
 use serde_json::{json, Value};
-use types::music::{Artist, Wave, Track, SampleOccurance, TrackSample};
+use types::music::{Artist, Wave, Track, SampleOccurance};
 
 pub mod types;
 pub mod yaml;
 pub mod build;
+pub mod wave;
+pub mod map;
+pub mod graph;
+pub mod stats;
+pub mod todo;
+pub mod cli;
+pub mod scaffold;
+pub mod fmt;
+pub mod import;
+pub mod musicbrainz;
+pub mod playlist;
+pub mod platform;
+pub mod links;
+pub mod slug;
+
+/// Where the site is published.
+pub const URL_SITE: &str = "https://phonkhub.com/";
+/// Where debug builds expect the output to be served, see `templates/base.html`.
+pub const URL_LOCAL: &str = "http://localhost:5500/out/";
 
 /// Parses a name (artist or album) for the file system.
 ///
-/// 1. Converts the name to lowercase
+/// 1. Transliterates the name to lowercase ASCII (see `slug::transliterate`)
 /// 2. Removes all special characters
 /// 3. Replaces spaces with hythens
 ///
+/// Names with nothing left, like ones written in Chinese, get an id made from their hash.
+///
 /// # Example
 /// ```
-/// let name = yar::parse_name("Don't Play & The Gang");
+/// let name = site::parse_name("Don't Play & The Gang");
 /// assert_eq!(name, "dont-play-and-the-gang");
+/// assert_eq!(site::parse_name("Мёртвый Ёжик"), "myortvyy-yozhik");
+/// assert_eq!(site::parse_name("Coração"), "coracao");
+/// assert_eq!(site::parse_name("ファンク"), "fanku");
+/// assert_eq!(site::parse_name("東京 Drift"), "drift");
 /// ```
 pub fn parse_name(name: &str) -> String {
     let is_space = |c: char| c == ' ';
     let is_special = |c: char| !c.is_ascii_alphanumeric() && !is_space(c) && c != '-';
-    name.to_lowercase()
+    let id = slug::transliterate(name)
         .replace(" & ", " and ")
         .replace(is_special, "")
         .replace(is_space, "-")
+        .trim_matches('-')
+        .to_owned();
+    if id.chars().any(|c| c.is_ascii_alphanumeric()) { return id; }
+    let hash = md5::compute(name);
+    format!("id-{:02x}{:02x}{:02x}{:02x}", hash[0], hash[1], hash[2], hash[3])
+}
+
+/// Key to order names by, case, diacritics, punctuation and a leading article do not count
+/// and other scripts go by their Latin spelling. An article is a word of its own, so it has
+/// to be followed by a space.
+///
+/// ```
+/// assert_eq!(site::collation_key("The Weeknd"), "weeknd");
+/// assert_eq!(site::collation_key("Élan"), "elan");
+/// assert_eq!(site::collation_key("Мёртвый Ёжик"), "myortvyy yozhik");
+/// assert_eq!(site::collation_key("A"), "a");
+/// assert_eq!(site::collation_key("A-Trak"), "a trak");
+/// assert_eq!(site::collation_key("El-P"), "el p");
+/// assert_eq!(site::collation_key("A$AP Rocky"), "a ap rocky");
+/// ```
+pub fn collation_key(name: &str) -> String {
+    let name = slug::transliterate(name);
+    let name = name.trim_start();
+    let without_article = ARTICLES
+        .iter()
+        .find_map(|article| name.strip_prefix(article)?.strip_prefix(char::is_whitespace))
+        .filter(|rest| rest.chars().any(char::is_alphanumeric))
+        .unwrap_or(name);
+    let words: Vec<&str> = without_article.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    if words.is_empty() { return name.to_owned(); }
+    words.join(" ")
+}
+
+/// Left out in front of names when ordering them.
+const ARTICLES: [&str; 9] = ["the", "a", "an", "el", "la", "los", "las", "le", "les"];
+
+/// Letter a name is listed under in the indexes, `A` to `Z` or `#` for anything else.
+///
+/// ```
+/// assert_eq!(site::index_letter("The Weeknd"), 'W');
+/// assert_eq!(site::index_letter("ファンク"), 'F');
+/// assert_eq!(site::index_letter("2Pac"), '#');
+/// assert_eq!(site::index_letter("A-Trak"), 'A');
+/// ```
+pub fn index_letter(name: &str) -> char {
+    match collation_key(name).chars().next() {
+        Some(c) if c.is_ascii_lowercase() => c.to_ascii_uppercase(),
+        _ => '#',
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -40,7 +114,32 @@ impl Color {
         Color { r, g, b }
     }
 
-    fn hex(&self) -> String { format!("#{:02x?}{:02x?}{:02x?}", self.r, self.g, self.b) }
+    pub fn hex(&self) -> String { format!("#{:02x?}{:02x?}{:02x?}", self.r, self.g, self.b) }
+
+    /// Parses a `#rrggbb` string.
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#')?;
+        if hex.len() != 6 { return None; }
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some(Color::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Adds two colors channel by channel, the same way the overlapping samples used to blend on the canvas.
+    pub fn add(&self, other: &Color) -> Color {
+        Color::new(
+            self.r.saturating_add(other.r),
+            self.g.saturating_add(other.g),
+            self.b.saturating_add(other.b),
+        )
+    }
+}
+
+/// Escapes text for use in generated SVG and XML.
+pub fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
 pub fn id_to_color(id: &str) -> Color {
@@ -80,6 +179,21 @@ pub fn str_to_duration(time: &str) -> Duration {
     }
 }
 
+/// Whether a time can be read by `str_to_duration` (`m:ss` or `h:mm:ss`).
+pub fn is_duration(time: &str) -> bool {
+    let parts: Vec<&str> = time.split(':').collect();
+    (2..=3).contains(&parts.len()) && parts.iter().all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Canonical way to write a time in the db, `m:ss` or `h:mm:ss` from an hour on.
+pub fn duration_normalize(duration: &Duration) -> String {
+    let hours = duration.num_hours();
+    if hours == 0 { return duration_format(duration); }
+    let min = duration.num_minutes() - hours * 60;
+    let sec = duration.num_seconds() - duration.num_minutes() * 60;
+    format!("{}:{:02}:{:02}", hours, min, sec)
+}
+
 pub fn duration_to_str(duration: &Duration) -> String {
     let min = duration.num_minutes();
     let sec = duration.num_seconds() - min * 60;
@@ -95,7 +209,7 @@ pub fn duration_format(duration: &Duration) -> String {
 pub fn calc_sample_pos(track: &Track, occurance: &SampleOccurance) -> (f32, f32) {
     let duration = occurance.to - occurance.from;
     let duration_seconds_sample = duration.num_seconds() as f32;
-    let duration_seconds_track = track.duration.num_seconds() as f32;
+    let duration_seconds_track = track.duration.unwrap_or_else(Duration::zero).num_seconds() as f32;
     let percent = duration_seconds_sample / duration_seconds_track;
     let width = (percent * 100 as f32);
 
@@ -104,31 +218,3 @@ pub fn calc_sample_pos(track: &Track, occurance: &SampleOccurance) -> (f32, f32)
 
     (width, left)
 }
-
-/// This should just serialize a vect of stucts but I couldn't get that working so we have this fn
-pub fn sample_to_colors(samples: &Vec<TrackSample>) -> String {
-    samples
-        .iter()
-        .flat_map(
-            |sample| sample.occurances
-                .iter()
-                .map(
-                    |occurs| {
-                        let color = get_sample_color(sample);
-                        let r = color.r;
-                        let g = color.g;
-                        let b = color.b;
-                        format!(r#"{{from: {}, to: {}, color: {}}}"#, occurs.from.num_seconds(), occurs.to.num_seconds(), format!("[{},{},{}]", r,g,b))
-                    }
-                    )
-        )
-        .join(",")
-}
-
-fn get_sample_color(sample: &TrackSample) -> Color {
-    match sample.r#type.as_str() {
-        "vocals" => Color::new(255, 0, 0),
-        "beat" => Color::new(0, 0, 255),
-        _ => Color::new(0, 0, 0)
-    }
-}
\ No newline at end of file
@@ -0,0 +1,194 @@
+use std::{collections::BTreeMap, fs::{read_to_string, write}, io::Error, path::Path, thread::sleep, time::{Duration, Instant}};
+
+use chrono::{Local, NaiveDate};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::yaml::Data;
+
+/// Gets the http status of a url, or why it could not be reached.
+pub trait Fetcher {
+    fn status(&self, url: &str) -> Result<u16, String>;
+}
+
+/// Asks the platforms themselves.
+pub struct Http {
+    agent: ureq::Agent,
+}
+
+impl Http {
+    pub fn new() -> Http {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(20))
+            .user_agent("phonkhub-site link checker")
+            .build();
+        Http { agent }
+    }
+}
+
+impl Default for Http {
+    fn default() -> Self { Http::new() }
+}
+
+impl Fetcher for Http {
+    /// Some sites do not answer `HEAD`, those get a `GET`.
+    fn status(&self, url: &str) -> Result<u16, String> {
+        let status = |result: Result<ureq::Response, ureq::Error>| match result {
+            Ok(response) => Ok(response.status()),
+            Err(ureq::Error::Status(code, _)) => Ok(code),
+            Err(error) => Err(error.to_string()),
+        };
+        match status(self.agent.head(url).call())? {
+            405 | 501 => status(self.agent.get(url).call()),
+            code => Ok(code),
+        }
+    }
+}
+
+/// Asks a local server instead, for `https://host/path` it requests `<server>/host/path`.
+/// A static file server over a folder of such paths stands in for the platforms.
+pub struct StandIn {
+    server: String,
+    http: Http,
+}
+
+impl StandIn {
+    pub fn new(server: &str) -> StandIn {
+        StandIn { server: server.trim_end_matches('/').to_owned(), http: Http::new() }
+    }
+}
+
+impl Fetcher for StandIn {
+    fn status(&self, url: &str) -> Result<u16, String> {
+        let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+        self.http.status(&format!("{}/{}", self.server, rest))
+    }
+}
+
+/// Statuses of urls from earlier runs, kept as JSON.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Cache {
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    status: u16,
+    checked: NaiveDate,
+}
+
+impl Cache {
+    /// An empty cache when there is no file yet.
+    pub fn read(path: &str) -> Result<Cache, Error> {
+        if !Path::new(path).exists() { return Ok(Cache::default()); }
+        let content = read_to_string(path)?;
+        Ok(serde_json::from_str(&content).unwrap_or_else(|error| panic!("Err reading: {}: {}", path, error)))
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Error> {
+        write(path, serde_json::to_string_pretty(self).unwrap() + "\n")
+    }
+
+    /// Status of a url checked less than `max_age` days ago.
+    fn get(&self, url: &str, today: NaiveDate, max_age: i64) -> Option<u16> {
+        let entry = self.entries.get(url)?;
+        if (today - entry.checked).num_days() >= max_age { return None; }
+        Some(entry.status)
+    }
+}
+
+/// How links are checked.
+pub struct Options {
+    /// Time between two requests.
+    pub delay: Duration,
+    /// Days a cached status is used before the url is checked again.
+    pub max_age: i64,
+}
+
+/// A link that did not work, with the db file it is written in.
+pub struct Dead {
+    pub file: String,
+    pub url: String,
+    /// The http status, or why the url could not be reached.
+    pub reason: String,
+}
+
+/// Every external url of the db with the file it is written in, sorted by file.
+pub fn collect(data: &Data) -> Vec<(String, String)> {
+    let path_artists = data.path.to_owned() + "artists/";
+    let mut links = vec![];
+    for artist in data.artists.values() {
+        let file = format!("{}{}/.artist.yml", path_artists, artist.id);
+        let urls = artist.urls.iter().chain([&artist.image_url]).chain(&artist.logo_url);
+        links.extend(urls.map(|url| (file.to_owned(), url.to_owned())));
+    }
+    for album in data.albums.values() {
+        let file = format!("{}{}/{}.yml", path_artists, album.artist_id, album.id);
+        links.extend(album.urls.iter().chain([&album.cover_url]).map(|url| (file.to_owned(), url.to_owned())));
+    }
+    for track in data.tracks.values() {
+        let file = format!("{}{}/{}.yml", path_artists, track.artist_id, track.album_id);
+        links.extend(track.locations.iter().map(|location| (file.to_owned(), location.url.to_owned())));
+    }
+    links
+        .into_iter()
+        .filter(|(_, url)| url.starts_with("http://") || url.starts_with("https://"))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Checks the links, each url once, and returns the ones that are dead.
+/// Statuses are taken from and put into the cache. Urls that could not be reached or
+/// were rate limited (429) are not cached, they are tried again next time.
+pub fn check(links: &[(String, String)], fetcher: &dyn Fetcher, cache: &mut Cache, options: &Options) -> Vec<Dead> {
+    let today = Local::now().date_naive();
+    let mut results: BTreeMap<&str, Result<u16, String>> = BTreeMap::new();
+    let mut last: Option<Instant> = None;
+    for url in links.iter().map(|(_, url)| url.as_str()).unique() {
+        if let Some(status) = cache.get(url, today, options.max_age) {
+            results.insert(url, Ok(status));
+            continue;
+        }
+        if let Some(last) = last {
+            sleep(options.delay.saturating_sub(last.elapsed()));
+        }
+        last = Some(Instant::now());
+        let result = fetcher.status(url);
+        if let Some(&status) = result.as_ref().ok().filter(|status| **status != RATE_LIMITED) {
+            cache.entries.insert(url.to_owned(), CacheEntry { status, checked: today });
+        }
+        results.insert(url, result);
+    }
+
+    links
+        .iter()
+        .filter_map(|(file, url)| {
+            let reason = match &results[url.as_str()] {
+                Ok(status) if is_alive(*status) => return None,
+                Ok(status) => status.to_string(),
+                Err(error) => error.to_owned(),
+            };
+            Some(Dead { file: file.to_owned(), url: url.to_owned(), reason })
+        })
+        .collect()
+}
+
+/// Dead links listed under the file they are written in.
+pub fn report(dead: &[Dead]) -> String {
+    let mut out = String::new();
+    for (file, links) in &dead.iter().group_by(|dead| &dead.file) {
+        out += &format!("{}\n", file);
+        for link in links {
+            out += &format!("    {} {}\n", link.reason, link.url);
+        }
+    }
+    out
+}
+
+const RATE_LIMITED: u16 = 429;
+
+/// Rate limited requests do not say anything about the link.
+fn is_alive(status: u16) -> bool {
+    status < 400 || status == RATE_LIMITED
+}
@@ -1,12 +1,15 @@
-use std::{io::{Error, ErrorKind}, fs::{read_dir, write, DirEntry, create_dir_all, create_dir}, collections::HashMap};
+use std::{io::{Error, ErrorKind}, fs::{read_dir, write, DirEntry, create_dir_all, create_dir}, collections::HashMap, env, process::exit};
 use chrono::{format::format, NaiveDate};
 use serde::Deserialize;
 // use site::types::music::{Album, Track, Location, get_countries, Country, Countries, Artist, Data, Artists, ArtistEntry, AlbumEntry, ArtistData, ArtistsByCountry, countries_to_hashmap, Features, TrackEntry};
-use site::{yaml::{read_data, Data}, types::music::{Artist}, build::build};
+use site::{yaml::{read_data, Data}, types::music::{Artist, Album, Track}, build::build, stats::Stats, cli::Args, scaffold::{new_artist, new_album}, fmt::format_db, import::{bandcamp::import_bandcamp, tags::import_tags}, musicbrainz::{seed_page, release_json}, playlist::{entries, to_format, by_release, FORMATS}, links::{self, Cache, Fetcher, Http, StandIn}};
 use askama::Template;
 
 const PATH_DB: &str = "./db/";
 const PATH_OUT: &str = "./out/";
+const PATH_LINKS_CACHE: &str = "./.links-cache.json";
+
+const USAGE: &str = "usage: site [build | stats | fmt [--check] | new artist | new album | import bandcamp <page.html> | import tags <folder> | export musicbrainz [--json] | export playlist --format <m3u8|xspf|jspf> | links [--server <url>]]";
 
 
 
@@ -40,10 +43,132 @@ const PATH_OUT: &str = "./out/";
 
 
 
-fn main() -> Result<(), Error> { 
-    let data = read_data(PATH_DB).unwrap();
-    build(PATH_OUT, &data)
- }
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(&args) {
+        eprintln!("error: {}", error);
+        exit(1)
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+    let command: Vec<&str> = args.iter().take(2).map(String::as_str).collect();
+    match command.as_slice() {
+        [] | ["build", ..] => {
+            let data = read_data(PATH_DB)?;
+            build(PATH_OUT, &data)
+        }
+        ["stats", ..] => {
+            let data = read_data(PATH_DB)?;
+            print!("{}", Stats::new(&data).report());
+            Ok(())
+        }
+        ["fmt", ..] => {
+            let check = Args::parse(&args[1..], &["check"]).has("check");
+            let changed = format_db(PATH_DB, check)?;
+            for path in &changed { println!("{}", path); }
+            if check && !changed.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidData, format!("{} files are not formatted, run `site fmt`", changed.len())));
+            }
+            Ok(())
+        }
+        ["new", "artist"] => {
+            let path = new_artist(PATH_DB, &Args::parse(&args[2..], &[]))?;
+            println!("created {}", path);
+            Ok(())
+        }
+        ["new", "album"] => {
+            let path = new_album(PATH_DB, &Args::parse(&args[2..], &[]))?;
+            println!("created {}", path);
+            Ok(())
+        }
+        ["import", "bandcamp"] => {
+            let path = import_bandcamp(PATH_DB, &Args::parse(&args[2..], &[]))?;
+            println!("created {}", path);
+            Ok(())
+        }
+        ["import", "tags"] => {
+            let path = import_tags(PATH_DB, &Args::parse(&args[2..], &["wave"]))?;
+            println!("created {}", path);
+            Ok(())
+        }
+        ["export", "musicbrainz"] => {
+            let args = Args::parse(&args[2..], &["json"]);
+            let data = read_data(PATH_DB)?;
+            let albums = select_albums(&data, &args)?;
+            if args.has("json") {
+                let releases: Vec<_> = albums.iter().map(|album| release_json(&data, album)).collect();
+                println!("{}", serde_json::to_string_pretty(&releases).unwrap());
+            } else {
+                print!("{}", seed_page(&data, &albums));
+            }
+            Ok(())
+        }
+        ["export", "playlist"] => {
+            let args = Args::parse(&args[2..], &[]);
+            let data = read_data(PATH_DB)?;
+            let format = args.get("format").unwrap_or_else(|| FORMATS[0].to_owned());
+            let (title, tracks) = select_tracks(&data, &args)?;
+            let playlist = to_format(&format, &title, &entries(&data, &tracks))
+                .ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("unknown playlist format: {}, use one of {}", format, FORMATS.join(", "))))?;
+            print!("{}", playlist);
+            Ok(())
+        }
+        ["links", ..] => {
+            let args = Args::parse(&args[1..], &[]);
+            let data = read_data(PATH_DB)?;
+            let number = |key: &str, default: u64| -> Result<u64, Error> {
+                let Some(value) = args.get(key) else { return Ok(default) };
+                value.parse().map_err(|_| Error::new(ErrorKind::InvalidInput, format!("--{} takes a number, not {}", key, value)))
+            };
+            let options = links::Options {
+                delay: std::time::Duration::from_millis(number("delay", 1000)?),
+                max_age: number("max-age", 7)? as i64,
+            };
+            let fetcher: Box<dyn Fetcher> = match args.get("server") {
+                Some(server) => Box::new(StandIn::new(&server)),
+                None => Box::new(Http::new()),
+            };
+            let path_cache = args.get("cache").unwrap_or_else(|| PATH_LINKS_CACHE.to_owned());
+            let mut cache = Cache::read(&path_cache)?;
+            let dead = links::check(&links::collect(&data), fetcher.as_ref(), &mut cache, &options);
+            cache.write(&path_cache)?;
+            print!("{}", links::report(&dead));
+            if !dead.is_empty() {
+                return Err(Error::new(ErrorKind::InvalidData, format!("{} dead links", dead.len())));
+            }
+            Ok(())
+        }
+        _ => Err(Error::new(ErrorKind::InvalidInput, format!("unknown command: {}\n{}", args.join(" "), USAGE))),
+    }
+}
+
+/// Title and tracks of the album picked with `--album <artist/album>` or of the artist picked with `--artist <id>`.
+fn select_tracks(data: &Data, args: &Args) -> Result<(String, Vec<Track>), Error> {
+    if let Some(id) = args.get("album") {
+        let album = data.albums.get(&id).ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("unknown album: {}", id)))?;
+        return Ok((album.name.to_owned(), data.get_tracks_in_album_sorted(&id)));
+    }
+    if let Some(id) = args.get("artist") {
+        let artist = data.artists.get(&id).ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("unknown artist: {}", id)))?;
+        return Ok((artist.name.to_owned(), by_release(data, data.get_tracks_by(&id).into_values().flatten().collect())));
+    }
+    Err(Error::new(ErrorKind::InvalidInput, "--album or --artist is required"))
+}
+
+/// Albums picked with `--album <artist/album>` or `--artist <id>`, all of them otherwise.
+fn select_albums(data: &Data, args: &Args) -> Result<Vec<Album>, Error> {
+    if let Some(id) = args.get("album") {
+        let album = data.albums.get(&id).ok_or_else(|| Error::new(ErrorKind::InvalidInput, format!("unknown album: {}", id)))?;
+        return Ok(vec![album.clone()]);
+    }
+    let mut albums: Vec<Album> = match args.get("artist") {
+        Some(id) => data.get_albums_by(&id),
+        None => data.albums.values().cloned().collect(),
+    };
+    albums.sort_by(|a, b| a.artist_id.cmp(&b.artist_id).then(a.released.cmp(&b.released)).then(a.id.cmp(&b.id)));
+    Ok(albums)
+}
 
 // fn get_data() -> Result<Data, Error>{
 //     let mut features = HashMap::new();
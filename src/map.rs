@@ -0,0 +1,205 @@
+use std::{collections::{BTreeMap, HashSet}, fmt::Write, fs::read_to_string, io::{Error, ErrorKind}, path::Path};
+
+use serde_json::Value;
+
+use crate::{types::music::Artist, yaml::{Country, Position}, Color, xml_escape};
+
+/// Rings of longitude and latitude points per country code, holes are rings too.
+pub type Outlines = BTreeMap<String, Vec<Vec<(f32, f32)>>>;
+
+/// Degrees of latitude and longitude covered by one tile.
+const DEGREES_TILE: f32 = 5.0;
+const COLUMNS: i32 = (360.0 / DEGREES_TILE) as i32;
+const ROWS: i32 = (180.0 / DEGREES_TILE) as i32;
+const SIZE_TILE: i32 = 12;
+const HEIGHT_LEGEND: i32 = 24;
+const COLOR_BACKGROUND: &str = "#111111";
+const COLOR_EMPTY: Color = Color { r: 42, g: 42, b: 42 };
+const COLOR_FULL: Color = Color { r: 138, g: 43, b: 226 };
+
+/// Renders a world map where every country is shaded by its artist count, countries
+/// with artists link to their page in the same directory.
+///
+/// Countries are drawn with their outlines when given, see [`read_outlines`]. Countries
+/// with artists but without an outline, too small for the shapes, get a dot at their
+/// position. Without outlines a tile map is drawn instead, for builds without the shapes.
+pub fn countries_to_svg(countries: &BTreeMap<String, Country>, artists: &BTreeMap<String, Vec<Artist>>, outlines: Option<&Outlines>) -> String {
+    let max = artists.values().map(|artists| artists.len()).max().unwrap_or(0);
+
+    let width = COLUMNS * SIZE_TILE;
+    let height = ROWS * SIZE_TILE + HEIGHT_LEGEND;
+    let mut svg = String::new();
+    write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#).unwrap();
+    write!(svg, r#"<rect width="{width}" height="{height}" fill="{COLOR_BACKGROUND}"/>"#).unwrap();
+
+    match outlines {
+        Some(outlines) => draw_outlines(&mut svg, countries, artists, max, outlines),
+        None => draw_tiles(&mut svg, countries, artists, max),
+    }
+
+    let y = ROWS * SIZE_TILE + HEIGHT_LEGEND / 2;
+    let steps = 8;
+    for step in 0..=steps {
+        let color = shade(step * max / steps, max).hex();
+        write!(svg, r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#, 40 + step as i32 * SIZE_TILE * 2, y - SIZE_TILE / 2, SIZE_TILE * 2, SIZE_TILE, color).unwrap();
+    }
+    let label = |x: i32, anchor: &str, text: usize| format!(
+        r#"<text x="{}" y="{}" fill="white" font-family="monospace" font-size="11" text-anchor="{}" dominant-baseline="middle">{}</text>"#,
+        x, y, anchor, text,
+    );
+    svg.push_str(&label(36, "end", 0));
+    svg.push_str(&label(44 + (steps as i32 + 1) * SIZE_TILE * 2, "start", max));
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Reads the outlines of a GeoJSON file of countries like the Natural Earth admin 0
+/// countries, `None` when the file does not exist.
+pub fn read_outlines(path: &str) -> Result<Option<Outlines>, Error> {
+    if !Path::new(path).exists() { return Ok(None); }
+    let json: Value = serde_json::from_str(&read_to_string(path)?)
+        .map_err(|err| Error::new(ErrorKind::InvalidData, format!("{}: {}", path, err)))?;
+
+    let mut outlines = Outlines::new();
+    for feature in json["features"].as_array().into_iter().flatten() {
+        // Natural Earth writes -99 for some countries in ISO_A2, ISO_A2_EH has them.
+        let code = ["ISO_A2_EH", "ISO_A2", "iso_a2"]
+            .iter()
+            .filter_map(|key| feature["properties"][key].as_str())
+            .find(|code| code.len() == 2);
+        let Some(code) = code else { continue };
+
+        let geometry = &feature["geometry"];
+        let polygons = match geometry["type"].as_str() {
+            Some("Polygon") => vec![&geometry["coordinates"]],
+            Some("MultiPolygon") => geometry["coordinates"].as_array().into_iter().flatten().collect(),
+            _ => continue,
+        };
+        let rings = outlines.entry(code.to_owned()).or_default();
+        for ring in polygons.into_iter().filter_map(Value::as_array).flatten() {
+            let points = ring
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|point| Some((point[0].as_f64()? as f32, point[1].as_f64()? as f32)))
+                .collect::<Vec<_>>();
+            if points.len() > 2 { rings.push(points); }
+        }
+    }
+    Ok(Some(outlines))
+}
+
+fn draw_outlines(svg: &mut String, countries: &BTreeMap<String, Country>, artists: &BTreeMap<String, Vec<Artist>>, max: usize, outlines: &Outlines) {
+    let count = |code: &str| artists.get(code).map_or(0, |artists| artists.len());
+    let scale = SIZE_TILE as f32 / DEGREES_TILE;
+    let point = |(lon, lat): (f32, f32)| ((lon + 180.0) * scale, (90.0 - lat) * scale);
+
+    for (code, rings) in outlines {
+        let mut d = String::new();
+        for ring in rings {
+            for (i, position) in ring.iter().enumerate() {
+                let (x, y) = point(*position);
+                write!(d, "{}{:.1} {:.1}", if i == 0 { "M" } else { "L" }, x, y).unwrap();
+            }
+            d.push('Z');
+        }
+        let count = count(code);
+        let color = shade(count, max).hex();
+        let tooltip = countries.get(code).map(|country| tooltip(country, count)).unwrap_or_default();
+        let path = format!(r#"<path d="{}" fill="{}" fill-rule="evenodd" stroke="{}" stroke-width="0.5">{}</path>"#, d, color, COLOR_BACKGROUND, tooltip);
+        link(svg, code, count, &path);
+    }
+
+    let dots = countries
+        .values()
+        .filter(|country| count(&country.code) > 0 && !outlines.contains_key(&country.code));
+    for country in dots {
+        let Some(position) = country.position else { continue };
+        let (x, y) = point((position.lon, position.lat));
+        let count = count(&country.code);
+        let circle = format!(
+            r#"<circle cx="{:.1}" cy="{:.1}" r="{}" fill="{}" stroke="white" stroke-width="0.5">{}</circle>"#,
+            x, y, SIZE_TILE / 4, shade(count, max).hex(), tooltip(country, count),
+        );
+        link(svg, &country.code, count, &circle);
+    }
+}
+
+/// Countries are placed at their position, when two land on the same tile the
+/// later one moves to the closest free tile. Countries with artists are placed
+/// first so they keep their spot.
+fn draw_tiles(svg: &mut String, countries: &BTreeMap<String, Country>, artists: &BTreeMap<String, Vec<Artist>>, max: usize) {
+    let count = |code: &str| artists.get(code).map_or(0, |artists| artists.len());
+    let mut placed: Vec<&Country> = countries
+        .values()
+        .filter(|country| country.position.is_some())
+        .collect();
+    placed.sort_by(|a, b| count(&b.code).cmp(&count(&a.code)).then(a.code.cmp(&b.code)));
+
+    let mut taken = HashSet::new();
+    for country in placed {
+        let (column, row) = free_tile(&taken, tile(country.position.unwrap()));
+        taken.insert((column, row));
+
+        let count = count(&country.code);
+        let color = shade(count, max).hex();
+        let rect = format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}">{}</rect>"#,
+            column * SIZE_TILE, row * SIZE_TILE, SIZE_TILE - 1, SIZE_TILE - 1, color, tooltip(country, count),
+        );
+        link(svg, &country.code, count, &rect);
+    }
+}
+
+fn tooltip(country: &Country, count: usize) -> String {
+    format!("<title>{} {}: {} artists</title>", xml_escape(&country.name), country.emoji, count)
+}
+
+/// Wraps the shape of a country in a link to its page when it has artists.
+fn link(svg: &mut String, code: &str, count: usize, shape: &str) {
+    if count > 0 {
+        write!(svg, r#"<a href="./{}.html">{}</a>"#, code, shape).unwrap();
+    } else {
+        svg.push_str(shape);
+    }
+}
+
+/// Equirectangular tile of a position.
+fn tile(position: Position) -> (i32, i32) {
+    let column = ((position.lon + 180.0) / DEGREES_TILE).floor() as i32;
+    let row = ((90.0 - position.lat) / DEGREES_TILE).floor() as i32;
+    (column.clamp(0, COLUMNS - 1), row.clamp(0, ROWS - 1))
+}
+
+/// Closest tile that is not taken yet, searching in growing rings.
+fn free_tile(taken: &HashSet<(i32, i32)>, (column, row): (i32, i32)) -> (i32, i32) {
+    for distance in 0..COLUMNS {
+        let mut ring: Vec<(i32, i32)> = vec![];
+        for dy in -distance..=distance {
+            for dx in -distance..=distance {
+                if dx.abs().max(dy.abs()) != distance { continue; }
+                ring.push((column + dx, row + dy));
+            }
+        }
+        ring.sort_by_key(|(c, r)| (c - column).pow(2) + (r - row).pow(2));
+        let free = ring
+            .into_iter()
+            .filter(|(c, r)| (0..COLUMNS).contains(c) && (0..ROWS).contains(r))
+            .find(|tile| !taken.contains(tile));
+        if let Some(tile) = free { return tile; }
+    }
+    (column, row)
+}
+
+/// Color between empty and full, the square root keeps countries with few artists visible.
+fn shade(count: usize, max: usize) -> Color {
+    if count == 0 || max == 0 { return COLOR_EMPTY; }
+    let t = (count as f32 / max as f32).sqrt();
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Color {
+        r: mix(COLOR_EMPTY.r, COLOR_FULL.r),
+        g: mix(COLOR_EMPTY.g, COLOR_FULL.g),
+        b: mix(COLOR_EMPTY.b, COLOR_FULL.b),
+    }
+}
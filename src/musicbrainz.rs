@@ -0,0 +1,167 @@
+use askama::Template;
+use itertools::Itertools;
+use serde_json::{json, Value};
+
+use crate::{yaml::Data, types::{music::{Album, Track, TrackArtist, ReleaseType}, date::PartialDate}, URL_SITE};
+
+const URL_RELEASE_ADD: &str = "https://musicbrainz.org/release/add";
+
+/// One name of an artist credit with the phrase leading to the next name.
+pub struct Credit {
+    pub name: String,
+    pub join_phrase: String,
+}
+
+/// A page of release editor forms, one button per album opens it prefilled on MusicBrainz.
+#[derive(Template)]
+#[template(path = "musicbrainz.html")]
+struct TemplateSeeds {
+    url: &'static str,
+    releases: Vec<(String, Vec<(String, String)>)>,
+}
+
+/// Release editor seeding page for the albums, see
+/// https://musicbrainz.org/doc/Development/Release_Editor_Seeding
+pub fn seed_page(data: &Data, albums: &[Album]) -> String {
+    let releases = albums
+        .iter()
+        .map(|album| (album.name.to_owned(), release_seed(data, album)))
+        .collect();
+    TemplateSeeds { url: URL_RELEASE_ADD, releases }.render().unwrap()
+}
+
+/// Form fields of the release editor for an album.
+pub fn release_seed(data: &Data, album: &Album) -> Vec<(String, String)> {
+    let mut fields = vec![("name".to_owned(), album.name.to_owned())];
+    // The release editor takes the primary and the secondary type as two `type` fields.
+    let (primary, secondary) = release_type(album);
+    fields.extend([Some(primary), secondary].into_iter().flatten().map(|r#type| ("type".to_owned(), r#type.to_lowercase())));
+    fields.push(("status".to_owned(), "official".to_owned()));
+    // Only the known part of the date, a circa year is no date to MusicBrainz.
+    if !matches!(album.released, PartialDate::Circa(_)) {
+        let parts = [("year", album.released.year().map(|year| year as u32)), ("month", album.released.month()), ("day", album.released.day())];
+        fields.extend(parts.into_iter().filter_map(|(part, value)| Some(("date.".to_owned() + part, value?.to_string()))));
+    }
+    fields.push(("mediums.0.format".to_owned(), "Digital Media".to_owned()));
+    let artist = TrackArtist { id: album.artist_id.to_owned(), r#for: None };
+    push_credits(&mut fields, "artist_credit", &credits(data, &[artist]));
+
+    let tracks = data.get_tracks_in_album_sorted(&album.key());
+    for (i, track) in tracks.iter().enumerate() {
+        let prefix = format!("mediums.0.track.{}", i);
+        fields.push((prefix.to_owned() + ".number", track.position.to_string()));
+        fields.push((prefix.to_owned() + ".name", track.name.to_owned()));
+        if let Some(duration) = track.duration {
+            fields.push((prefix.to_owned() + ".length", duration.num_milliseconds().to_string()));
+        }
+        push_credits(&mut fields, &(prefix + ".artist_credit"), &credits(data, &track.artists));
+    }
+
+    for (i, url) in urls(album, &tracks).iter().enumerate() {
+        fields.push((format!("urls.{}.url", i), url.to_owned()));
+    }
+    fields.push(("edit_note".to_owned(), format!("Imported from {}artists/{}/{}/", URL_SITE, album.artist_id, album.id)));
+    fields
+}
+
+/// An album in the JSON format of the MusicBrainz web service.
+pub fn release_json(data: &Data, album: &Album) -> Value {
+    let tracks = data.get_tracks_in_album_sorted(&album.key());
+    let tracks_json: Vec<Value> = tracks
+        .iter()
+        .map(|track| {
+            let relations: Vec<Value> = track.locations.iter().map(|location| relation(&location.url)).collect();
+            json!({
+                "position": track.position,
+                "number": track.position.to_string(),
+                "title": track.name,
+                "length": track.duration.map(|duration| duration.num_milliseconds()),
+                "artist-credit": credits_json(&credits(data, &track.artists)),
+                "recording": {
+                    "title": track.name,
+                    "length": track.duration.map(|duration| duration.num_milliseconds()),
+                    "relations": relations,
+                },
+            })
+        })
+        .collect();
+    let artist = TrackArtist { id: album.artist_id.to_owned(), r#for: None };
+    let relations: Vec<Value> = album.urls.iter().map(|url| relation(url)).collect();
+
+    json!({
+        "title": album.name,
+        "status": "Official",
+        "date": if matches!(album.released, PartialDate::Circa(_)) { None } else { album.released.iso() },
+        "release-group": {
+            "primary-type": release_type(album).0,
+            "secondary-types": release_type(album).1.into_iter().collect::<Vec<_>>(),
+        },
+        "artist-credit": credits_json(&credits(data, &[artist])),
+        "media": [{
+            "position": 1,
+            "format": "Digital Media",
+            "track-count": tracks.len(),
+            "tracks": tracks_json,
+        }],
+        "relations": relations,
+    })
+}
+
+/// Main artists joined by `&`, followed by the ones featured for something.
+pub fn credits(data: &Data, artists: &[TrackArtist]) -> Vec<Credit> {
+    let name = |artist: &TrackArtist| data.artists.get(&artist.id).map(|found| found.name.to_owned()).unwrap_or_else(|| artist.id.to_owned());
+    let (main, featured): (Vec<&TrackArtist>, Vec<&TrackArtist>) = artists.iter().partition(|artist| artist.r#for.is_none());
+    let main_count = main.len();
+    let featured_count = featured.len();
+
+    let mut credits = vec![];
+    for (i, artist) in main.into_iter().enumerate() {
+        let join_phrase = if i + 1 < main_count { " & " } else if featured_count > 0 { " feat. " } else { "" };
+        credits.push(Credit { name: name(artist), join_phrase: join_phrase.to_owned() });
+    }
+    for (i, artist) in featured.into_iter().enumerate() {
+        let join_phrase = if i + 1 < featured_count { " & " } else { "" };
+        credits.push(Credit { name: name(artist), join_phrase: join_phrase.to_owned() });
+    }
+    credits
+}
+
+fn push_credits(fields: &mut Vec<(String, String)>, prefix: &str, credits: &[Credit]) {
+    for (i, credit) in credits.iter().enumerate() {
+        let prefix = format!("{}.names.{}", prefix, i);
+        fields.push((prefix.to_owned() + ".name", credit.name.to_owned()));
+        fields.push((prefix.to_owned() + ".artist.name", credit.name.to_owned()));
+        fields.push((prefix + ".join_phrase", credit.join_phrase.to_owned()));
+    }
+}
+
+fn credits_json(credits: &[Credit]) -> Value {
+    credits
+        .iter()
+        .map(|credit| json!({ "name": credit.name, "joinphrase": credit.join_phrase, "artist": { "name": credit.name } }))
+        .collect()
+}
+
+fn relation(url: &str) -> Value {
+    json!({ "type": "free streaming", "target-type": "url", "url": { "resource": url } })
+}
+
+/// Album links, and the links of the track for singles as a release has no track links.
+fn urls(album: &Album, tracks: &[Track]) -> Vec<String> {
+    let mut urls = album.urls.clone();
+    if let [track] = tracks {
+        urls.extend(track.locations.iter().map(|location| location.url.to_owned()));
+    }
+    urls.into_iter().unique().collect()
+}
+
+/// Primary type of the release group and the secondary one, if any.
+fn release_type(album: &Album) -> (&'static str, Option<&'static str>) {
+    match album.r#type {
+        ReleaseType::Album => ("Album", None),
+        ReleaseType::Ep => ("EP", None),
+        ReleaseType::Single => ("Single", None),
+        ReleaseType::Mixtape => ("Album", Some("Mixtape/Street")),
+        ReleaseType::Compilation => ("Album", Some("Compilation")),
+    }
+}
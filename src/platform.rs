@@ -0,0 +1,241 @@
+use chrono::Duration;
+
+use crate::duration_to_str;
+
+/// Which platform a link is on, matched on where platforms are treated differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlatformId {
+    Bandcamp,
+    SoundCloud,
+    YouTube,
+    Spotify,
+    Apple,
+    Deezer,
+    Tidal,
+    Audiomack,
+    Yandex,
+    Vk,
+    Instagram,
+    TikTok,
+    X,
+    Website,
+}
+
+impl PlatformId {
+    /// Lowercase name, used in `data-platform` attributes of the pages.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlatformId::Bandcamp => "bandcamp",
+            PlatformId::SoundCloud => "soundcloud",
+            PlatformId::YouTube => "youtube",
+            PlatformId::Spotify => "spotify",
+            PlatformId::Apple => "apple",
+            PlatformId::Deezer => "deezer",
+            PlatformId::Tidal => "tidal",
+            PlatformId::Audiomack => "audiomack",
+            PlatformId::Yandex => "yandex",
+            PlatformId::Vk => "vk",
+            PlatformId::Instagram => "instagram",
+            PlatformId::TikTok => "tiktok",
+            PlatformId::X => "x",
+            PlatformId::Website => "website",
+        }
+    }
+}
+
+impl std::fmt::Display for PlatformId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A site links of artists, albums and tracks can point to.
+pub struct Platform {
+    pub id: PlatformId,
+    pub label: &'static str,
+    /// Links are styled by `.loc-<class>` in styles.css.
+    pub class: &'static str,
+    /// Hosts of the platform, subdomains included. Empty matches any host.
+    hosts: &'static [&'static str],
+    /// Id of the artist, release or track on the platform.
+    parse_id: fn(&Parts) -> Option<String>,
+    /// Link that starts playing at an offset, for platforms that can.
+    start_at: Option<fn(&str, &Duration) -> String>,
+}
+
+/// A url with the platform it belongs to and what it points to there.
+#[derive(Debug, Clone)]
+pub struct Link {
+    pub platform: &'static Platform,
+    pub id: String,
+    pub url: String,
+}
+
+impl std::fmt::Debug for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.id.as_str())
+    }
+}
+
+impl Link {
+    /// Name shown on the link, the host for websites that are no platform.
+    pub fn label(&self) -> String {
+        if self.platform.id != PlatformId::Website { return self.platform.label.to_owned(); }
+        Parts::new(&self.url).map(|parts| parts.host.to_owned()).unwrap_or_else(|| self.platform.label.to_owned())
+    }
+}
+
+impl Platform {
+    /// The url starting at `at`, or the url as is when the platform can not.
+    pub fn start_at(&self, url: &str, at: &Duration) -> String {
+        match self.start_at {
+            Some(start_at) => start_at(url, at),
+            None => url.to_owned(),
+        }
+    }
+}
+
+/// Every known platform, in the order links are shown and preferred for listening.
+/// The last one takes any url.
+pub static PLATFORMS: [Platform; 14] = [
+    Platform { id: PlatformId::Bandcamp, label: "bandcamp", class: "bc", hosts: &["bandcamp.com"], parse_id: id_bandcamp, start_at: None },
+    Platform { id: PlatformId::SoundCloud, label: "soundcloud", class: "sc", hosts: &["soundcloud.com"], parse_id: id_path, start_at: Some(start_soundcloud) },
+    Platform { id: PlatformId::YouTube, label: "youtube", class: "yt", hosts: &["youtube.com", "youtu.be"], parse_id: id_youtube, start_at: Some(start_youtube) },
+    Platform { id: PlatformId::Spotify, label: "spotify", class: "spot", hosts: &["spotify.com"], parse_id: id_kind_last, start_at: None },
+    Platform { id: PlatformId::Apple, label: "apple", class: "apple", hosts: &["music.apple.com", "itunes.apple.com"], parse_id: id_apple, start_at: None },
+    Platform { id: PlatformId::Deezer, label: "deezer", class: "deezer", hosts: &["deezer.com"], parse_id: id_kind_last, start_at: None },
+    Platform { id: PlatformId::Tidal, label: "tidal", class: "tidal", hosts: &["tidal.com"], parse_id: id_kind_last, start_at: None },
+    Platform { id: PlatformId::Audiomack, label: "audiomack", class: "audiomack", hosts: &["audiomack.com"], parse_id: id_path, start_at: None },
+    Platform { id: PlatformId::Yandex, label: "yandex music", class: "yandex", hosts: &["music.yandex.ru", "music.yandex.com", "music.yandex.by", "music.yandex.kz"], parse_id: id_path, start_at: None },
+    Platform { id: PlatformId::Vk, label: "vk", class: "vk", hosts: &["vk.com", "vk.ru"], parse_id: id_path, start_at: None },
+    Platform { id: PlatformId::Instagram, label: "instagram", class: "insta", hosts: &["instagram.com"], parse_id: id_handle, start_at: None },
+    Platform { id: PlatformId::TikTok, label: "tiktok", class: "tiktok", hosts: &["tiktok.com"], parse_id: id_handle, start_at: None },
+    Platform { id: PlatformId::X, label: "x", class: "x", hosts: &["x.com", "twitter.com"], parse_id: id_handle, start_at: None },
+    Platform { id: PlatformId::Website, label: "website", class: "web", hosts: &[], parse_id: id_path, start_at: None },
+];
+
+/// Finds the platform of a url. Urls of a known host without an id, like the front page,
+/// and urls of unknown hosts are websites.
+///
+/// ```
+/// use site::platform::{parse_url, PlatformId};
+///
+/// let platform_and_id = |url| {
+///     let link = parse_url(url);
+///     (link.platform.id, link.id)
+/// };
+/// assert_eq!(platform_and_id("https://www.deezer.com/en/track/123"), (PlatformId::Deezer, "track/123".to_owned()));
+/// assert_eq!(platform_and_id("https://tidal.com/browse/album/456"), (PlatformId::Tidal, "album/456".to_owned()));
+/// assert_eq!(platform_and_id("https://m.vk.com/djsmokey"), (PlatformId::Vk, "djsmokey".to_owned()));
+/// assert_eq!(platform_and_id("https://music.yandex.ru/album/1/track/2"), (PlatformId::Yandex, "album/1/track/2".to_owned()));
+/// assert_eq!(platform_and_id("https://twitter.com/djsmokey"), (PlatformId::X, "djsmokey".to_owned()));
+/// assert_eq!(platform_and_id("https://x.com/@djsmokey"), (PlatformId::X, "djsmokey".to_owned()));
+/// assert_eq!(platform_and_id("https://youtu.be/abc?t=10"), (PlatformId::YouTube, "abc".to_owned()));
+/// assert_eq!(platform_and_id("https://tidal.com/"), (PlatformId::Website, "tidal.com".to_owned()));
+/// ```
+pub fn parse_url(url: &str) -> Link {
+    let website = PLATFORMS.last().unwrap();
+    let Some(parts) = Parts::new(url) else {
+        return Link { platform: website, id: url.to_owned(), url: url.to_owned() };
+    };
+    for platform in &PLATFORMS {
+        if !platform.hosts.iter().any(|host| parts.is_host(host)) { continue; }
+        if let Some(id) = (platform.parse_id)(&parts) {
+            return Link { platform, id, url: url.to_owned() };
+        }
+    }
+    let id = parts.host.to_owned() + &id_path(&parts).map(|path| "/".to_owned() + &path).unwrap_or_default();
+    Link { platform: website, id, url: url.to_owned() }
+}
+
+/// Links of urls in the order of `PLATFORMS`, links of one platform keep their order.
+pub fn parse_urls(urls: &[String]) -> Vec<Link> {
+    let mut links: Vec<Link> = urls.iter().map(|url| parse_url(url)).collect();
+    sort_links(&mut links);
+    links
+}
+
+/// Orders links by `PLATFORMS`, links of one platform keep their order.
+pub fn sort_links(links: &mut [Link]) {
+    links.sort_by_key(|link| PLATFORMS.iter().position(|platform| platform.id == link.platform.id));
+}
+
+/// A url split up, without `www.` and `m.` in front of the host.
+struct Parts<'a> {
+    host: &'a str,
+    segments: Vec<&'a str>,
+    query: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Parts<'a> {
+    fn new(url: &'a str) -> Option<Parts<'a>> {
+        let (_, rest) = url.split_once("://")?;
+        let rest = rest.split('#').next().unwrap();
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = host.strip_prefix("www.").or_else(|| host.strip_prefix("m.")).unwrap_or(host);
+        if host.is_empty() { return None; }
+        let segments = path.split('/').filter(|segment| !segment.is_empty()).collect();
+        let query = query.split('&').filter_map(|pair| pair.split_once('=')).collect();
+        Some(Parts { host, segments, query })
+    }
+
+    fn is_host(&self, host: &str) -> bool {
+        self.host == host || self.host.ends_with(&(".".to_owned() + host))
+    }
+
+    fn query(&self, key: &str) -> Option<&'a str> {
+        self.query.iter().find(|(k, _)| *k == key).map(|(_, value)| *value)
+    }
+}
+
+fn id_path(parts: &Parts) -> Option<String> {
+    if parts.segments.is_empty() { None } else { Some(parts.segments.join("/")) }
+}
+
+/// `djsmokey/track/lean-on-me` of `djsmokey.bandcamp.com/track/lean-on-me`.
+fn id_bandcamp(parts: &Parts) -> Option<String> {
+    let subdomain = parts.host.strip_suffix(".bandcamp.com")?;
+    Some([vec![subdomain], parts.segments.clone()].concat().join("/"))
+}
+
+/// Video id, or the path of channels and playlists.
+fn id_youtube(parts: &Parts) -> Option<String> {
+    if parts.host == "youtu.be" { return parts.segments.first().map(|id| id.to_string()); }
+    if let Some(id) = parts.query("v") { return Some(id.to_owned()); }
+    if let Some(list) = parts.query("list") { return Some("playlist/".to_owned() + list); }
+    match parts.segments.as_slice() {
+        ["shorts" | "embed" | "live", id, ..] => Some(id.to_string()),
+        _ => id_path(parts),
+    }
+}
+
+/// `track/123` of `deezer.com/en/track/123`, `open.spotify.com/intl-de/track/123` or `tidal.com/browse/track/123`.
+fn id_kind_last(parts: &Parts) -> Option<String> {
+    const KINDS: [&str; 6] = ["track", "album", "artist", "playlist", "show", "episode"];
+    let position = parts.segments.iter().position(|segment| KINDS.contains(segment))?;
+    let id = parts.segments.get(position + 1)?;
+    Some(parts.segments[position].to_owned() + "/" + id)
+}
+
+/// `album/123` of `music.apple.com/us/album/name/123`, `song/456` with `?i=456`.
+fn id_apple(parts: &Parts) -> Option<String> {
+    if let Some(id) = parts.query("i") { return Some("song/".to_owned() + id); }
+    let kind = parts.segments.iter().find(|segment| ["album", "artist", "song", "playlist"].contains(segment))?;
+    Some(kind.to_string() + "/" + parts.segments.last()?)
+}
+
+/// Account name without `@`, followed by the rest of the path for posts.
+fn id_handle(parts: &Parts) -> Option<String> {
+    let path = id_path(parts)?;
+    Some(path.strip_prefix('@').unwrap_or(&path).to_owned())
+}
+
+fn start_soundcloud(url: &str, at: &Duration) -> String {
+    url.to_owned() + &duration_to_str(at)
+}
+
+fn start_youtube(url: &str, at: &Duration) -> String {
+    let separator = if url.contains('?') { "&" } else { "?" };
+    format!("{}{}t={}", url, separator, at.num_seconds())
+}
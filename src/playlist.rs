@@ -0,0 +1,125 @@
+use std::fmt::Write;
+
+use chrono::Duration;
+use itertools::Itertools;
+use serde_json::json;
+
+use crate::{yaml::Data, types::music::Track, xml_escape, URL_SITE};
+
+/// File formats a playlist can be written in, by extension.
+pub const FORMATS: [&str; 3] = ["m3u8", "xspf", "jspf"];
+
+/// A track as a playlist entry.
+pub struct Entry {
+    pub title: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub duration: Option<Duration>,
+    /// Best location to listen at, starting at its offset where the platform supports it.
+    pub url: Option<String>,
+    /// Page of the track on the site.
+    pub info: String,
+}
+
+impl Entry {
+    pub fn new(data: &Data, track: &Track) -> Entry {
+        let artists = track.artists
+            .iter()
+            .map(|artist| data.artists.get(&artist.id).map(|found| found.name.to_owned()).unwrap_or_else(|| artist.id.to_owned()))
+            .collect();
+        Entry {
+            title: track.name.to_owned(),
+            artists,
+            album: data.get_album(&track.album_key()).name,
+            duration: track.duration,
+            url: best_url(track),
+            info: format!("{}artists/{}/{}/{}.html", URL_SITE, track.artist_id, track.album_id, track.id),
+        }
+    }
+
+    fn creator(&self) -> String {
+        self.artists.join(", ")
+    }
+}
+
+/// Entries of tracks in the given order.
+pub fn entries(data: &Data, tracks: &[Track]) -> Vec<Entry> {
+    tracks.iter().map(|track| Entry::new(data, track)).collect()
+}
+
+/// Tracks ordered by the release of their album and their position.
+pub fn by_release(data: &Data, tracks: Vec<Track>) -> Vec<Track> {
+    tracks
+        .into_iter()
+        .sorted_by_key(|track| (data.get_album(&track.album_key()).released, track.album_key(), track.position))
+        .collect()
+}
+
+/// Location to put in a playlist, the first one in the order of the platforms.
+/// It starts at the offset of the location where the platform supports it.
+pub fn best_url(track: &Track) -> Option<String> {
+    track.links().into_iter().next().map(|link| link.url)
+}
+
+/// Writes a playlist in one of `FORMATS`.
+pub fn to_format(format: &str, title: &str, entries: &[Entry]) -> Option<String> {
+    match format {
+        "m3u8" => Some(to_m3u8(title, entries)),
+        "xspf" => Some(to_xspf(title, entries)),
+        "jspf" => Some(to_jspf(title, entries)),
+        _ => None,
+    }
+}
+
+/// Extended M3U, entries without a location are left out as M3U needs one.
+/// Unknown durations are written as `-1`.
+pub fn to_m3u8(title: &str, entries: &[Entry]) -> String {
+    let mut out = String::new();
+    writeln!(out, "#EXTM3U").unwrap();
+    writeln!(out, "#PLAYLIST:{}", title).unwrap();
+    for entry in entries {
+        let Some(url) = &entry.url else { continue };
+        writeln!(out, "#EXTINF:{},{} - {}", entry.duration.map_or(-1, |duration| duration.num_seconds()), entry.creator(), entry.title).unwrap();
+        writeln!(out, "#EXTALB:{}", entry.album).unwrap();
+        writeln!(out, "{}", url).unwrap();
+    }
+    out
+}
+
+/// https://xspf.org/spec
+pub fn to_xspf(title: &str, entries: &[Entry]) -> String {
+    let mut out = String::new();
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(out, r#"<playlist version="1" xmlns="http://xspf.org/ns/0/">"#).unwrap();
+    writeln!(out, "  <title>{}</title>", xml_escape(title)).unwrap();
+    writeln!(out, "  <trackList>").unwrap();
+    for entry in entries {
+        writeln!(out, "    <track>").unwrap();
+        if let Some(url) = &entry.url { writeln!(out, "      <location>{}</location>", xml_escape(url)).unwrap(); }
+        writeln!(out, "      <title>{}</title>", xml_escape(&entry.title)).unwrap();
+        writeln!(out, "      <creator>{}</creator>", xml_escape(&entry.creator())).unwrap();
+        writeln!(out, "      <album>{}</album>", xml_escape(&entry.album)).unwrap();
+        if let Some(duration) = entry.duration { writeln!(out, "      <duration>{}</duration>", duration.num_milliseconds()).unwrap(); }
+        writeln!(out, "      <info>{}</info>", xml_escape(&entry.info)).unwrap();
+        writeln!(out, "    </track>").unwrap();
+    }
+    writeln!(out, "  </trackList>").unwrap();
+    writeln!(out, "</playlist>").unwrap();
+    out
+}
+
+/// XSPF as JSON, https://xspf.org/jspf
+pub fn to_jspf(title: &str, entries: &[Entry]) -> String {
+    let tracks: Vec<_> = entries
+        .iter()
+        .map(|entry| json!({
+            "location": entry.url.iter().collect::<Vec<_>>(),
+            "title": entry.title,
+            "creator": entry.creator(),
+            "album": entry.album,
+            "duration": entry.duration.map(|duration| duration.num_milliseconds()),
+            "info": entry.info,
+        }))
+        .collect();
+    serde_json::to_string_pretty(&json!({ "playlist": { "title": title, "track": tracks } })).unwrap()
+}
@@ -0,0 +1,155 @@
+use std::{io::{Error, ErrorKind, stdin, IsTerminal}, path::Path, fs::{create_dir_all, write, remove_file, remove_dir}, collections::HashMap};
+
+
+use crate::{cli::{Args, prompt}, fmt::{to_canonical_yaml, format_artist, format_album}, types::{date::PartialDate, music::{TrackStatus, ReleaseType}}, yaml::{YamlArtist, YamlAlbum, YamlTrack, YamlCollectiveMember, get_countries, read_data}, parse_name, is_duration};
+
+/// Writes a new `.artist.yml` from the flags (or prompts) and returns its path.
+/// When the db does not load with it, the file and its new folder are removed again.
+///
+/// `site new artist --name "DJ Smokey" --image <url> --country US --url <url> --member <id> --alias "Lil Smokey"`
+pub fn new_artist(path_db: &str, args: &Args) -> Result<String, Error> {
+    let name = args.get_or_prompt("name", true)?.unwrap();
+    let id = parse_name(&name);
+    let path_artist = path_db.to_owned() + "artists/" + &id + "/";
+    let path = path_artist.to_owned() + ".artist.yml";
+    if Path::new(&path).exists() { return Err(invalid(format!("{} already exists", path))); }
+
+    let image = args.get_or_prompt("image", true)?.unwrap();
+    let logo = args.get_or_prompt("logo", false)?;
+    let description = args.get_or_prompt("description", false)?;
+    let country = args.get_or_prompt("country", false)?.map(|code| code.to_uppercase());
+    if let Some(code) = &country {
+        if !get_countries()?.contains_key(code) { return Err(invalid(format!("unknown country code: {}", code))); }
+    }
+    let urls = args.get_all_or_prompt("url")?;
+    let members = args.get_all("member");
+    let collective_members = if members.is_empty() { None } else {
+        Some(members.into_iter().map(|name| YamlCollectiveMember { name, joined: None, left: None }).collect())
+    };
+
+    let aliases = args.get_all("alias");
+    let aliases = if aliases.is_empty() { None } else { Some(aliases) };
+    let mut yaml = YamlArtist { name, image, logo, urls, description, country, collective_members, aliases, names: None };
+    let is_new_folder = !Path::new(&path_artist).exists();
+    create_dir_all(&path_artist)?;
+    write(&path, to_canonical_yaml(&mut yaml, format_artist))?;
+    if let Err(error) = validate(path_db) {
+        remove_file(&path)?;
+        if is_new_folder { remove_dir(&path_artist)?; }
+        return Err(error);
+    }
+    Ok(path)
+}
+
+/// Writes a new album file from the flags (or prompts) and returns its path.
+///
+/// `site new album --artist dj-smokey --name "Smoked Out" --genre phonk --released 2016-04-20 --cover <url> --track "Lean On Me|2:05"`
+///
+/// `--type` (album, ep, mixtape, compilation or single) is optional, without it the
+/// track count decides between album and single.
+pub fn new_album(path_db: &str, args: &Args) -> Result<String, Error> {
+    let id_artist = args.get_or_prompt("artist", true)?.unwrap();
+    let path_artist = path_db.to_owned() + "artists/" + &id_artist + "/";
+    if !Path::new(&(path_artist.to_owned() + ".artist.yml")).exists() {
+        return Err(invalid(format!("unknown artist: {}, add it with `site new artist` first", id_artist)));
+    }
+
+    let name = args.get_or_prompt("name", true)?.unwrap();
+    let id = parse_name(&name);
+    let path = path_artist + &id + ".yml";
+    if Path::new(&path).exists() { return Err(invalid(format!("{} already exists", path))); }
+
+    let genre = args.get_or_prompt("genre", true)?.unwrap();
+    let released = args.get_or_prompt("released", true)?.unwrap();
+    let released = PartialDate::parse(&released)
+        .ok_or_else(|| invalid(format!("released has to be YYYY-MM-DD, YYYY-MM, YYYY, ~YYYY or unknown: {}", released)))?;
+    let r#type = args.get("type")
+        .map(|r#type| ReleaseType::parse(&r#type).ok_or_else(|| invalid(format!("type has to be album, ep, mixtape, compilation or single: {}", r#type))))
+        .transpose()?;
+    let cover = args.get_or_prompt("cover", true)?.unwrap();
+    let urls = args.get_all_or_prompt("url")?;
+
+    let mut tracks = HashMap::new();
+    for (i, track) in read_tracks(args)?.into_iter().enumerate() {
+        tracks.insert((i + 1).to_string(), track);
+    }
+    if tracks.is_empty() { return Err(invalid("at least one --track is required".to_owned())); }
+    let track_count = tracks.len().try_into().map_err(|_| invalid("too many tracks".to_owned()))?;
+    let urls = if urls.is_empty() { None } else { Some(urls) };
+
+    let yaml = YamlAlbum { name, artists: None, genre, released, r#type, cover, tracks, track_count, urls };
+    write_album(path_db, &id_artist, yaml)
+}
+
+/// Writes the file of an album by an existing artist, in the layout of `site fmt`, and returns its path.
+/// When the db does not load with it, the file is removed again.
+pub fn write_album(path_db: &str, id_artist: &str, mut yaml: YamlAlbum) -> Result<String, Error> {
+    let path_artist = path_db.to_owned() + "artists/" + id_artist + "/";
+    if !Path::new(&(path_artist.to_owned() + ".artist.yml")).exists() {
+        return Err(invalid(format!("unknown artist: {}, add it with `site new artist` first", id_artist)));
+    }
+    let id = parse_name(&yaml.name);
+    let path = path_artist + &id + ".yml";
+    if Path::new(&path).exists() { return Err(invalid(format!("{} already exists", path))); }
+    let mut ids_track: HashMap<String, &str> = HashMap::new();
+    for track in yaml.tracks.values() {
+        if let Some(taken) = ids_track.insert(parse_name(&track.name), &track.name) {
+            return Err(invalid(format!("tracks \"{}\" and \"{}\" both get the id \"{}\"", taken, track.name, parse_name(&track.name))));
+        }
+    }
+
+    write(&path, to_canonical_yaml(&mut yaml, format_album))?;
+    if let Err(error) = validate(path_db) {
+        remove_file(&path)?;
+        return Err(error);
+    }
+    Ok(path)
+}
+
+/// Tracks from `--track "name|duration"`, or typed in one by one. Tracks without a
+/// duration, like lost ones, are given as `--track "name"`.
+fn read_tracks(args: &Args) -> Result<Vec<YamlTrack>, Error> {
+    let mut tracks = vec![];
+    let given = args.get_all("track");
+    if !given.is_empty() || !stdin().is_terminal() {
+        for track in given {
+            let (name, duration) = track.rsplit_once('|').unwrap_or((&track, ""));
+            tracks.push(new_track(name.trim(), duration.trim())?);
+        }
+        return Ok(tracks);
+    }
+
+    loop {
+        let position = tracks.len() + 1;
+        let name = prompt(&format!("track {} name (empty to finish)", position))?;
+        if name.is_empty() { return Ok(tracks); }
+        let duration = prompt(&format!("track {} duration (empty if unknown)", position))?;
+        tracks.push(new_track(&name, &duration)?);
+    }
+}
+
+/// A track with an empty `duration` has none.
+pub fn new_track(name: &str, duration: &str) -> Result<YamlTrack, Error> {
+    if !duration.is_empty() && !is_duration(duration) { return Err(invalid(format!("duration has to be m:ss or h:mm:ss: {}", duration))); }
+    Ok(YamlTrack {
+        name: name.to_owned(),
+        duration: Some(duration.to_owned()).filter(|duration| !duration.is_empty()),
+        status: TrackStatus::Released,
+        artists: None,
+        artist: None,
+        location: vec![],
+        sample: None,
+        lyrics: None,
+        wave: None,
+    })
+}
+
+/// Reads the whole db again so a bad file shows up right away. Failed checks of the
+/// loader come back as the error, a file that does not parse aborts like in `site build`.
+fn validate(path_db: &str) -> Result<(), Error> {
+    read_data(path_db).map(|_| ())
+}
+
+pub fn invalid(message: String) -> Error {
+    Error::new(ErrorKind::InvalidInput, message)
+}
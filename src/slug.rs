@@ -0,0 +1,121 @@
+/// Lowercase ASCII spelling of a name: Latin letters lose their diacritics, Cyrillic and
+/// kana are romanized. Characters of other scripts are kept as they are.
+pub fn transliterate(name: &str) -> String {
+    let chars: Vec<char> = name.to_lowercase().chars().map(katakana_to_hiragana).collect();
+    let mut out = String::new();
+    let mut double_next = false;
+    for (i, c) in chars.iter().enumerate() {
+        let next = chars.get(i + 1).copied();
+        match c {
+            // Combining marks left over from lowercasing, like the dot of `İ`.
+            '\u{300}'..='\u{36f}' => {}
+            'っ' => double_next = true,
+            'ー' => if let Some(vowel) = out.chars().last().filter(|c| "aeiou".contains(*c)) { out.push(vowel) },
+            // `sh`, `ch` and `j` take the vowel without the `y`: `しゃ` is `sha`.
+            'ゃ' | 'ゅ' | 'ょ' => {
+                let roman = small_y(*c);
+                if out.ends_with("sh") || out.ends_with("ch") || out.ends_with('j') { out.push_str(&roman[1..]) } else { out.push_str(roman) }
+            }
+            'ぁ' | 'ぃ' | 'ぅ' | 'ぇ' | 'ぉ' => {
+                // `ふぁ` is `fa`, not `fua`.
+                if out.ends_with('u') && !out.ends_with("uu") { out.pop(); }
+                out.push_str(kana(small_vowel(*c)).unwrap());
+            }
+            _ => {
+                let Some(roman) = kana(*c).or_else(|| cyrillic(*c)).or_else(|| latin(*c)) else {
+                    out.push(*c);
+                    continue;
+                };
+                let mut roman = roman.to_owned();
+                if double_next {
+                    double_next = false;
+                    let first = if roman.starts_with("ch") { 't' } else { roman.chars().next().unwrap() };
+                    roman.insert(0, first);
+                }
+                // `きゃ` is `kya`, the `i` goes.
+                if matches!(next, Some('ゃ' | 'ゅ' | 'ょ')) && roman.len() > 1 && roman.ends_with('i') { roman.pop(); }
+                out.push_str(&roman);
+            }
+        }
+    }
+    out
+}
+
+fn katakana_to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap(),
+        _ => c,
+    }
+}
+
+fn small_y(c: char) -> &'static str {
+    match c { 'ゃ' => "ya", 'ゅ' => "yu", _ => "yo" }
+}
+
+fn small_vowel(c: char) -> char {
+    match c { 'ぁ' => 'あ', 'ぃ' => 'い', 'ぅ' => 'う', 'ぇ' => 'え', _ => 'お' }
+}
+
+/// Hepburn spelling of hiragana, katakana is turned into hiragana before.
+fn kana(c: char) -> Option<&'static str> {
+    Some(match c {
+        'あ' => "a", 'い' => "i", 'う' => "u", 'え' => "e", 'お' => "o",
+        'か' => "ka", 'き' => "ki", 'く' => "ku", 'け' => "ke", 'こ' => "ko",
+        'が' => "ga", 'ぎ' => "gi", 'ぐ' => "gu", 'げ' => "ge", 'ご' => "go",
+        'さ' => "sa", 'し' => "shi", 'す' => "su", 'せ' => "se", 'そ' => "so",
+        'ざ' => "za", 'じ' => "ji", 'ず' => "zu", 'ぜ' => "ze", 'ぞ' => "zo",
+        'た' => "ta", 'ち' => "chi", 'つ' => "tsu", 'て' => "te", 'と' => "to",
+        'だ' => "da", 'ぢ' => "ji", 'づ' => "zu", 'で' => "de", 'ど' => "do",
+        'な' => "na", 'に' => "ni", 'ぬ' => "nu", 'ね' => "ne", 'の' => "no",
+        'は' => "ha", 'ひ' => "hi", 'ふ' => "fu", 'へ' => "he", 'ほ' => "ho",
+        'ば' => "ba", 'び' => "bi", 'ぶ' => "bu", 'べ' => "be", 'ぼ' => "bo",
+        'ぱ' => "pa", 'ぴ' => "pi", 'ぷ' => "pu", 'ぺ' => "pe", 'ぽ' => "po",
+        'ま' => "ma", 'み' => "mi", 'む' => "mu", 'め' => "me", 'も' => "mo",
+        'や' => "ya", 'ゆ' => "yu", 'よ' => "yo",
+        'ら' => "ra", 'り' => "ri", 'る' => "ru", 'れ' => "re", 'ろ' => "ro",
+        'わ' => "wa", 'ゐ' => "i", 'ゑ' => "e", 'を' => "o", 'ん' => "n",
+        'ゔ' => "vu", 'ゕ' => "ka", 'ゖ' => "ke", 'ゎ' => "wa",
+        '・' => " ",
+        _ => return None,
+    })
+}
+
+/// Russian, Ukrainian and Belarusian letters.
+fn cyrillic(c: char) -> Option<&'static str> {
+    Some(match c {
+        'а' => "a", 'б' => "b", 'в' => "v", 'г' => "g", 'д' => "d", 'е' => "e", 'ё' => "yo",
+        'ж' => "zh", 'з' => "z", 'и' => "i", 'й' => "y", 'к' => "k", 'л' => "l", 'м' => "m",
+        'н' => "n", 'о' => "o", 'п' => "p", 'р' => "r", 'с' => "s", 'т' => "t", 'у' => "u",
+        'ф' => "f", 'х' => "kh", 'ц' => "ts", 'ч' => "ch", 'ш' => "sh", 'щ' => "shch", 'ъ' => "",
+        'ы' => "y", 'ь' => "", 'э' => "e", 'ю' => "yu", 'я' => "ya",
+        'є' => "ye", 'і' => "i", 'ї' => "yi", 'ґ' => "g", 'ў' => "u",
+        _ => return None,
+    })
+}
+
+/// Latin letters with diacritics and ligatures.
+fn latin(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        'ß' => "ss", 'æ' => "ae", 'œ' => "oe", 'þ' => "th",
+        _ => return None,
+    })
+}
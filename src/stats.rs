@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fmt::Write, process::Command};
+
+use chrono::Duration;
+use itertools::Itertools;
+
+use crate::{yaml::Data, types::music::Track, duration_format};
+
+/// How many entries the ranked lists keep.
+const TOP: usize = 20;
+
+/// Numbers about the whole database, shown on the stats page and by `site stats`.
+pub struct Stats {
+    pub artists: usize,
+    pub albums: usize,
+    pub tracks: usize,
+    pub releases_per_year: Vec<(i32, usize)>,
+    /// Artist ids by the number of tracks they are on.
+    pub tracks_per_artist: Vec<(String, usize)>,
+    /// Artist ids by the number of tracks they are on outside their own albums.
+    pub most_featured: Vec<(String, usize)>,
+    /// Sample sources (`artist - name`) by the number of tracks sampling them.
+    pub most_sampled: Vec<(String, usize)>,
+    pub duration_average: Duration,
+    /// Country codes by their number of artists.
+    pub countries: Vec<(String, usize)>,
+    pub tracks_without_wave: usize,
+    pub tracks_without_samples: usize,
+    pub tracks_without_lyrics: usize,
+    /// Albums in the database by month, from the history of the db repository.
+    pub growth: Vec<(String, usize)>,
+}
+
+impl Stats {
+    pub fn new(data: &Data) -> Stats {
+        let releases_per_year = count(data.albums.values().filter_map(|album| album.released.year()))
+            .into_iter()
+            .sorted()
+            .collect();
+
+        let tracks_per_artist = top(count(data.tracks.values().flat_map(|track| track.artists.iter().map(|artist| artist.id.clone()).unique())));
+
+        let most_featured = top(count(data.tracks.values().flat_map(|track| {
+            let id_album_artist = data.get_album(&track.album_key()).artist_id;
+            track.artists
+                .iter()
+                .map(|artist| artist.id.clone())
+                .filter(move |id| *id != id_album_artist)
+                .unique()
+        })));
+
+        let most_sampled = top(count(data.tracks.values().flat_map(|track| {
+            track.samples
+                .iter()
+                .map(|sample| sample.artist.clone() + " - " + &sample.name)
+                .unique()
+        })));
+
+        let durations: Vec<Duration> = data.tracks.values().filter_map(|track| track.duration).collect();
+        let seconds: i64 = durations.iter().map(|duration| duration.num_seconds()).sum();
+        let duration_average = Duration::seconds(seconds / durations.len().max(1) as i64);
+
+        let countries = count(data.artists.values().filter_map(|artist| artist.country_code.clone()))
+            .into_iter()
+            .sorted_by(|(code_a, a), (code_b, b)| b.cmp(a).then(code_a.cmp(code_b)))
+            .collect();
+
+        let tracks_without = |has: fn(&Track) -> bool| data.tracks.values().filter(|track| !has(track)).count();
+
+        Stats {
+            artists: data.artists.len(),
+            albums: data.albums.len(),
+            tracks: data.tracks.len(),
+            releases_per_year,
+            tracks_per_artist,
+            most_featured,
+            most_sampled,
+            duration_average,
+            countries,
+            tracks_without_wave: tracks_without(|track| track.wave.is_some()),
+            tracks_without_samples: tracks_without(|track| !track.samples.is_empty()),
+            tracks_without_lyrics: tracks_without(|track| track.lyrics.is_some()),
+            growth: read_growth(&data.path),
+        }
+    }
+
+    /// Plain text version for the terminal.
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "{} artists, {} albums, {} tracks", self.artists, self.albums, self.tracks).unwrap();
+        writeln!(out, "average track duration: {}", duration_format(&self.duration_average)).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "missing:").unwrap();
+        writeln!(out, "  wave     {:>5} / {}", self.tracks_without_wave, self.tracks).unwrap();
+        writeln!(out, "  samples  {:>5} / {}", self.tracks_without_samples, self.tracks).unwrap();
+        writeln!(out, "  lyrics   {:>5} / {}", self.tracks_without_lyrics, self.tracks).unwrap();
+
+        let mut section = |title: &str, rows: Vec<(String, usize)>| {
+            if rows.is_empty() { return; }
+            writeln!(out).unwrap();
+            writeln!(out, "{}:", title).unwrap();
+            for (label, count) in rows { writeln!(out, "  {:>5}  {}", count, label).unwrap(); }
+        };
+        section("releases per year", self.releases_per_year.iter().map(|(year, count)| (year.to_string(), *count)).collect());
+        section("tracks per artist", self.tracks_per_artist.clone());
+        section("most featured", self.most_featured.clone());
+        section("most sampled", self.most_sampled.clone());
+        section("countries", self.countries.clone());
+        section("albums over time", self.growth.clone());
+        out
+    }
+}
+
+/// Largest count of a list.
+pub fn max<T>(rows: &[(T, usize)]) -> usize {
+    rows.iter().map(|(_, count)| *count).max().unwrap_or(0)
+}
+
+/// Percentage of `count` in `max`, used for the bars on the stats page.
+pub fn percent(count: &usize, max: &usize) -> usize {
+    if *max == 0 { return 0; }
+    count * 100 / max
+}
+
+fn count<T: std::hash::Hash + Eq>(items: impl Iterator<Item = T>) -> HashMap<T, usize> {
+    let mut counts = HashMap::new();
+    for item in items { *counts.entry(item).or_insert(0) += 1; }
+    counts
+}
+
+fn top(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    counts
+        .into_iter()
+        .sorted_by(|(id_a, a), (id_b, b)| b.cmp(a).then(id_a.cmp(id_b)))
+        .take(TOP)
+        .collect()
+}
+
+/// Counts album files by the month they were added to the db repository.
+/// Empty when the db is not a git checkout.
+fn read_growth(path_db: &str) -> Vec<(String, usize)> {
+    let output = Command::new("git")
+        .args(["-C", path_db, "log", "--reverse", "--diff-filter=A", "--name-only", "--date=format:%Y-%m", "--format=@%ad"])
+        .output();
+    let Ok(output) = output else { return vec![] };
+    if !output.status.success() { return vec![]; }
+
+    let mut growth: Vec<(String, usize)> = vec![];
+    let mut month = String::new();
+    let mut total = 0;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(date) = line.strip_prefix('@') {
+            month = date.to_owned();
+            continue;
+        }
+        let is_album = line.starts_with("artists/") && line.ends_with(".yml") && !line.ends_with("/.artist.yml");
+        if !is_album { continue; }
+        total += 1;
+        match growth.last_mut() {
+            Some((last, count)) if *last == month => *count = total,
+            _ => growth.push((month.clone(), total)),
+        }
+    }
+    growth
+}
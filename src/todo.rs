@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{yaml::Data, types::music::{Album, Artist, Track}};
+
+const URL_DB: &str = "https://github.com/phonkhub/db";
+
+/// Link to create the `.artist.yml` of an artist on GitHub.
+pub fn url_new_artist(id_artist: &str) -> String {
+    format!("{}/new/main/artists/?filename=artists/{}/.artist.yml", URL_DB, id_artist)
+}
+
+/// Link to edit the `.artist.yml` of an artist on GitHub.
+pub fn url_edit_artist(id_artist: &str) -> String {
+    format!("{}/edit/main/artists/{}/.artist.yml", URL_DB, id_artist)
+}
+
+/// Link to edit the file of an album on GitHub.
+pub fn url_edit_album(album: &Album) -> String {
+    format!("{}/edit/main/artists/{}/{}.yml", URL_DB, album.artist_id, album.id)
+}
+
+/// Everything contributors could fill in, each list is sorted.
+pub struct Todo {
+    /// Artist ids without an `.artist.yml` with how often they are referenced.
+    pub artists_missing: Vec<(String, usize)>,
+    pub artists_without_country: Vec<Artist>,
+    pub albums_without_urls: Vec<Album>,
+    pub tracks_without_wave: Vec<Track>,
+    pub tracks_without_samples: Vec<Track>,
+    pub tracks_without_lyrics: Vec<Track>,
+}
+
+impl Todo {
+    pub fn new(data: &Data) -> Todo {
+        let mut references: HashMap<&String, usize> = HashMap::new();
+        let ids_track = data.tracks.values().flat_map(|track| track.artists.iter().map(|artist| &artist.id));
+        let ids_member = data.artists
+            .values()
+            .flat_map(|artist| artist.collective_members.iter().flatten().map(|member| &member.id));
+        for id in ids_track.chain(ids_member) {
+            if data.artists.contains_key(id) { continue; }
+            *references.entry(id).or_insert(0) += 1;
+        }
+        let artists_missing = references
+            .into_iter()
+            .map(|(id, count)| (id.clone(), count))
+            .sorted_by(|(id_a, a), (id_b, b)| b.cmp(a).then(id_a.cmp(id_b)))
+            .collect();
+
+        let artists_without_country = data.artists
+            .values()
+            .filter(|artist| artist.country_code.is_none())
+            .sorted_by_key(|artist| &artist.id)
+            .cloned()
+            .collect();
+
+        let albums_without_urls = data.albums
+            .values()
+            .filter(|album| album.urls.is_empty())
+            .sorted_by_key(|album| (&album.artist_id, &album.id))
+            .cloned()
+            .collect();
+
+        let tracks_without = |has: fn(&Track) -> bool| -> Vec<Track> {
+            data.tracks
+                .values()
+                .filter(|track| !has(track))
+                .sorted_by_key(|track| (&track.artist_id, &track.album_id, track.position))
+                .cloned()
+                .collect()
+        };
+
+        Todo {
+            artists_missing,
+            artists_without_country,
+            albums_without_urls,
+            tracks_without_wave: tracks_without(|track| track.wave.is_some()),
+            tracks_without_samples: tracks_without(|track| !track.samples.is_empty()),
+            tracks_without_lyrics: tracks_without(|track| track.lyrics.is_some()),
+        }
+    }
+}
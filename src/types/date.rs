@@ -0,0 +1,166 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize, Serializer, de::Visitor};
+
+/// A release date that is only known as far as it is. In the yml files it is written
+/// `1995-06-12`, `1995-06`, `1995`, `~1995` for around that year or `unknown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PartialDate {
+    #[default]
+    Unknown,
+    Circa(i32),
+    Year(i32),
+    Month(i32, u32),
+    Day(NaiveDate),
+}
+
+impl PartialDate {
+    /// Parses the yml form, `None` for anything else.
+    ///
+    /// ```
+    /// use site::types::date::PartialDate;
+    /// assert_eq!(PartialDate::parse("1995"), Some(PartialDate::Year(1995)));
+    /// assert_eq!(PartialDate::parse("1995-06"), Some(PartialDate::Month(1995, 6)));
+    /// assert_eq!(PartialDate::parse("~1995"), Some(PartialDate::Circa(1995)));
+    /// assert_eq!(PartialDate::parse("1995-13"), None);
+    /// ```
+    pub fn parse(text: &str) -> Option<PartialDate> {
+        let text = text.trim();
+        if text == "unknown" { return Some(PartialDate::Unknown); }
+        if let Some(year) = text.strip_prefix('~') { return parse_year(year).map(PartialDate::Circa); }
+        match text.split('-').collect::<Vec<_>>().as_slice() {
+            [year] => parse_year(year).map(PartialDate::Year),
+            [year, month] => {
+                let year = parse_year(year)?;
+                let month = month.parse().ok().filter(|month| (1..=12).contains(month))?;
+                Some(PartialDate::Month(year, month))
+            }
+            _ => NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().map(PartialDate::Day),
+        }
+    }
+
+    pub fn year(&self) -> Option<i32> {
+        match self {
+            PartialDate::Unknown => None,
+            PartialDate::Circa(year) | PartialDate::Year(year) | PartialDate::Month(year, _) => Some(*year),
+            PartialDate::Day(date) => Some(date.year()),
+        }
+    }
+
+    pub fn month(&self) -> Option<u32> {
+        match self {
+            PartialDate::Month(_, month) => Some(*month),
+            PartialDate::Day(date) => Some(date.month()),
+            _ => None,
+        }
+    }
+
+    pub fn day(&self) -> Option<u32> {
+        match self {
+            PartialDate::Day(date) => Some(date.day()),
+            _ => None,
+        }
+    }
+
+    /// The known part as `YYYY`, `YYYY-MM` or `YYYY-MM-DD`, circa dates give their year.
+    pub fn iso(&self) -> Option<String> {
+        match self {
+            PartialDate::Unknown => None,
+            PartialDate::Circa(year) | PartialDate::Year(year) => Some(format!("{:04}", year)),
+            PartialDate::Month(year, month) => Some(format!("{:04}-{:02}", year, month)),
+            PartialDate::Day(date) => Some(date.format("%Y-%m-%d").to_string()),
+        }
+    }
+
+    /// Year, month, day and how exact the date is. Within a year dates that are less
+    /// exact come first, undated releases come after all others.
+    fn key(&self) -> (bool, i32, u32, u32, u8) {
+        let exactness = match self {
+            PartialDate::Unknown => 0,
+            PartialDate::Circa(_) => 0,
+            PartialDate::Year(_) => 1,
+            PartialDate::Month(..) => 2,
+            PartialDate::Day(_) => 3,
+        };
+        (self.year().is_none(), self.year().unwrap_or(0), self.month().unwrap_or(0), self.day().unwrap_or(0), exactness)
+    }
+}
+
+fn parse_year(year: &str) -> Option<i32> {
+    if year.len() != 4 { return None; }
+    year.parse().ok()
+}
+
+impl From<NaiveDate> for PartialDate {
+    fn from(date: NaiveDate) -> Self { PartialDate::Day(date) }
+}
+
+impl Ord for PartialDate {
+    fn cmp(&self, other: &Self) -> Ordering { self.key().cmp(&other.key()) }
+}
+
+impl PartialOrd for PartialDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// How the date is shown on the pages.
+impl Display for PartialDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PartialDate::Unknown => f.write_str("unknown"),
+            PartialDate::Circa(year) => write!(f, "c. {}", year),
+            _ => f.write_str(&self.iso().unwrap()),
+        }
+    }
+}
+
+/// The yml form, years are written as numbers.
+impl Serialize for PartialDate {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer {
+        match self {
+            PartialDate::Unknown => serializer.serialize_str("unknown"),
+            PartialDate::Circa(year) => serializer.serialize_str(&format!("~{}", year)),
+            PartialDate::Year(year) => serializer.serialize_i32(*year),
+            _ => serializer.serialize_str(&self.iso().unwrap()),
+        }
+    }
+}
+
+struct VisitorPartialDate;
+
+impl<'de> Visitor<'de> for VisitorPartialDate {
+    type Value = PartialDate;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a date like 1995-06-12, 1995-06, 1995, ~1995 or unknown")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error, {
+        PartialDate::parse(v).ok_or_else(|| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error, {
+        self.visit_str(&v.to_string())
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error, {
+        self.visit_str(&v.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PartialDate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de> {
+        deserializer.deserialize_any(VisitorPartialDate)
+    }
+}
@@ -1 +1,2 @@
-pub mod music;
\ No newline at end of file
+pub mod music;
+pub mod date;
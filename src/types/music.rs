@@ -1,6 +1,12 @@
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+
 use chrono::{NaiveDate, Duration};
 
+use crate::platform::{Link, PlatformId, parse_url, parse_urls, sort_links};
+
+use super::date::PartialDate;
+
 #[derive(Debug, Clone, Serialize)]
 pub struct Artist {
     pub id: String,
@@ -11,39 +17,23 @@ pub struct Artist {
     pub collective_members: Option<Vec<CollectiveMember>>,
     pub logo_url: Option<String>,
     pub urls: Vec<String>,
+    /// Other names the artist releases under.
+    pub aliases: Vec<String>,
+    /// The name in other scripts or languages, by language code.
+    pub names: BTreeMap<String, String>,
 }
 
 impl Artist {
-    fn url(&self, url_key: &str) -> Option<String> {
-        for url in &self.urls {
-            let is_soundcloud = url.contains(url_key);
-            if is_soundcloud { return Some(url.to_owned());}
-        }
-        None
-    }
-
-    pub fn url_bandcamp(&self) -> Option<String> { self.url(URL_BANDCAMP) }
-    pub fn url_soundcloud(&self) -> Option<String> { self.url(URL_SOUNDCLOUD) }
-    pub fn url_youtube(&self) -> Option<String> { self.url(URL_YOUTUBE) }
-    pub fn url_spotify(&self) -> Option<String> { self.url(URL_SPOTIFY) }
-    pub fn url_apple(&self) -> Option<String> { self.url(URL_APPLE) }
+    /// Links of the artist, ordered by platform.
+    pub fn links(&self) -> Vec<Link> { parse_urls(&self.urls) }
 }
 
-
 impl Album {
-    fn url(&self, url_key: &str) -> Option<String> {
-        for url in &self.urls {
-            let is_soundcloud = url.contains(url_key);
-            if is_soundcloud { return Some(url.to_owned());}
-        }
-        None
-    }
+    /// Links of the album, ordered by platform.
+    pub fn links(&self) -> Vec<Link> { parse_urls(&self.urls) }
 
-    pub fn url_bandcamp(&self) -> Option<String> { self.url(URL_BANDCAMP) }
-    pub fn url_soundcloud(&self) -> Option<String> { self.url(URL_SOUNDCLOUD) }
-    pub fn url_youtube(&self) -> Option<String> { self.url(URL_YOUTUBE) }
-    pub fn url_spotify(&self) -> Option<String> { self.url(URL_SPOTIFY) }
-    pub fn url_apple(&self) -> Option<String> { self.url(URL_APPLE) }
+    /// Key of the album in `Data::albums`, `artist/album`.
+    pub fn key(&self) -> String { format!("{}/{}", self.artist_id, self.id) }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -71,7 +61,7 @@ where
     S: Serializer {
     match dt {
         Some(dur) => dur.num_seconds().serialize(serializer),
-        _ => unreachable!()
+        None => serializer.serialize_none(),
     }
 }
 
@@ -82,7 +72,8 @@ pub struct Album {
     pub name: String,
     pub artist_id: String,
     pub genres: Vec<String>,
-    pub released: NaiveDate,
+    pub released: PartialDate,
+    pub r#type: ReleaseType,
     #[serde(serialize_with = "serialize_dur")]
     pub duration: Duration,
     pub cover_url: String,
@@ -97,13 +88,105 @@ pub struct Track {
     pub position: u8,
     pub artist_id: String,
     pub album_id: String,
-    #[serde(serialize_with = "serialize_dur")]
-    pub duration: Duration,
+    /// Unknown for lost tapes and snippets nobody timed.
+    #[serde(serialize_with = "serialize_dur_opt")]
+    pub duration: Option<Duration>,
+    pub status: TrackStatus,
     pub artists: Vec<TrackArtist>,
     pub locations: Vec<Location>,
     pub samples: Vec<TrackSample>,
     #[serde(skip)]
     pub wave: Option<Wave>,
+    #[serde(skip)]
+    pub lyrics: Option<String>,
+}
+
+/// What kind of release an album is, discographies are grouped in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseType {
+    Album,
+    Ep,
+    Mixtape,
+    Compilation,
+    Single,
+}
+
+impl ReleaseType {
+    pub const ALL: [ReleaseType; 5] = [ReleaseType::Album, ReleaseType::Ep, ReleaseType::Mixtape, ReleaseType::Compilation, ReleaseType::Single];
+
+    /// Albums without a type in their file are singles when they have one track.
+    pub fn from_track_count(track_count: u8) -> ReleaseType {
+        if track_count == 1 { ReleaseType::Single } else { ReleaseType::Album }
+    }
+
+    /// How the type is written in the yml files and the query.
+    pub fn id(&self) -> &'static str {
+        match self {
+            ReleaseType::Album => "album",
+            ReleaseType::Ep => "ep",
+            ReleaseType::Mixtape => "mixtape",
+            ReleaseType::Compilation => "compilation",
+            ReleaseType::Single => "single",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<ReleaseType> {
+        ReleaseType::ALL.into_iter().find(|r#type| r#type.id() == id.to_lowercase())
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ReleaseType::Ep => "EP",
+            _ => self.id(),
+        }
+    }
+
+    /// Heading of the type in discographies.
+    pub fn label_plural(&self) -> String {
+        self.label().to_owned() + "s"
+    }
+}
+
+/// Whether a track came out and how much of it is around.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackStatus {
+    #[default]
+    Released,
+    /// Known from the artist, never put out.
+    Unreleased,
+    /// Only a part of it is around.
+    Snippet,
+    /// Known to exist, no recording is around.
+    Lost,
+    /// Put out without the artist.
+    Leaked,
+}
+
+impl TrackStatus {
+    pub fn is_released(&self) -> bool { *self == TrackStatus::Released }
+
+    /// How the status is written in the yml files, the badges and the query.
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrackStatus::Released => "released",
+            TrackStatus::Unreleased => "unreleased",
+            TrackStatus::Snippet => "snippet",
+            TrackStatus::Lost => "lost",
+            TrackStatus::Leaked => "leaked",
+        }
+    }
+}
+
+/// A list of tracks put together by the community, from `playlists/<id>.yml`.
+#[derive(Debug, Clone)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// In the order of the file.
+    pub tracks: Vec<Track>,
 }
 
 pub struct Sample {
@@ -115,30 +198,25 @@ pub enum SampleMedia {
     Song(Track),
 }
 
-const URL_BANDCAMP: &str = "bandcamp.com";
-const URL_SOUNDCLOUD: &str = "soundcloud.com";
-const URL_YOUTUBE: &str = "youtube.com";
-const URL_SPOTIFY: &str = "spotify.com";
-const URL_APPLE: &str = "apple.com";
-
-impl Track {
-    fn location(&self, url: &str) -> Option<Location> {
-        for location in &self.locations {
-            let is_soundcloud = location.url.contains(url);
-            if is_soundcloud { return Some(location.to_owned());}
+impl Sample {
+    /// Page of the sampled media, from the root of the site.
+    pub fn path(&self) -> String {
+        match &self.media {
+            SampleMedia::Song(track) => format!("artists/{}/{}/{}.html", track.artist_id, track.album_id, track.id),
         }
-        None
     }
+}
 
-    pub fn location_bandcamp(&self) -> Option<Location> { self.location(URL_BANDCAMP) }
-
-    pub fn location_soundcloud(&self) -> Option<Location> { self.location(URL_SOUNDCLOUD) }
-
-    pub fn location_youtube(&self) -> Option<Location> { self.location(URL_YOUTUBE) }
+impl Track {
+    /// Key of the album of the track in `Data::albums`.
+    pub fn album_key(&self) -> String { format!("{}/{}", self.artist_id, self.album_id) }
 
-    pub fn location_spotify(&self) -> Option<Location> { self.location(URL_SPOTIFY) }
-    
-    pub fn location_apple(&self) -> Option<Location> { self.location(URL_APPLE) }
+    /// Links of the locations, ordered by platform and starting at their offset.
+    pub fn links(&self) -> Vec<Link> {
+        let mut links: Vec<Link> = self.locations.iter().map(|location| location.link()).collect();
+        sort_links(&mut links);
+        links
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -152,6 +230,61 @@ pub struct Location {
     pub url: String,
     #[serde(serialize_with = "serialize_dur_opt", skip_serializing_if = "Option::is_none")]
     pub at: Option<Duration>,
+    /// Id of the track on the platform when the url does not hold it, like the track id of Bandcamp.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+}
+
+/// A player of a location that can be put on a page.
+pub struct Embed {
+    pub platform: PlatformId,
+    pub url: String,
+    pub height: u32,
+}
+
+impl Location {
+    /// Link of the location, the url starts at `at` where the platform supports it.
+    pub fn link(&self) -> Link {
+        let mut link = parse_url(&self.url);
+        if let Some(at) = &self.at { link.url = link.platform.start_at(&link.url, at); }
+        link
+    }
+
+    /// Iframe player of the location. YouTube starts at `at` by itself, SoundCloud is
+    /// moved there by the page script, Bandcamp players can not start anywhere else.
+    pub fn embed(&self) -> Option<Embed> {
+        let link = parse_url(&self.url);
+        match link.platform.id {
+            PlatformId::Bandcamp => {
+                let id = self.id.as_ref()?;
+                let url = format!("https://bandcamp.com/EmbeddedPlayer/track={}/size=large/bgcol=333333/linkcol=0f91ff/artwork=small/transparent=true/", id);
+                Some(Embed { platform: PlatformId::Bandcamp, url, height: 120 })
+            }
+            PlatformId::SoundCloud => {
+                let url = format!("https://w.soundcloud.com/player/?url={}&color=%23ff5500&auto_play=false&visual=false", url_encode(&self.url));
+                Some(Embed { platform: PlatformId::SoundCloud, url, height: 166 })
+            }
+            // Channels and playlists have a path as id, only videos can be embedded.
+            PlatformId::YouTube if !link.id.contains('/') => {
+                let start = self.at.map(|at| at.num_seconds()).unwrap_or(0);
+                let url = format!("https://www.youtube.com/embed/{}?start={}&enablejsapi=1", link.id, start);
+                Some(Embed { platform: PlatformId::YouTube, url, height: 315 })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Percent encodes everything but unreserved characters, for urls in query strings.
+fn url_encode(text: &str) -> String {
+    let mut out = String::new();
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -0,0 +1,137 @@
+use std::fmt::Write;
+
+use chrono::Duration;
+
+use crate::{types::music::{Track, Wave}, yaml::SampleTypes, Color, calc_sample_pos, xml_escape};
+
+const WIDTH: usize = 600;
+const HEIGHT_WAVE: usize = 64;
+const HEIGHT_LANE: usize = 8;
+const HEIGHT_LEGEND: usize = 20;
+const COLOR_BACKGROUND: &str = "#111111";
+const COLOR_WAVE: Color = Color { r: 255, g: 255, b: 255 };
+/// Points kept when a wave is computed from audio.
+const POINTS: usize = 1000;
+
+/// Renders a track wave with its sample occurances as a standalone SVG.
+///
+/// The wave is drawn one bar per pixel, colored by the samples playing at that
+/// point. Below it every sample gets a lane marking where it occurs, followed
+/// by a legend of the sample types used.
+///
+/// Sample types are validated while loading, so every one has a color here. Samples are
+/// placed by the duration of the track, so tracks without one get no wave.
+pub fn wave_to_svg(track: &Track, wave: &Wave, sample_types: &SampleTypes) -> String {
+    let lanes = track.samples.len();
+    let types = types_used(track);
+    let height_lanes = lanes * HEIGHT_LANE;
+    let height_legend = if types.is_empty() { 0 } else { HEIGHT_LEGEND };
+    let height = HEIGHT_WAVE + height_lanes + height_legend;
+
+    let mut svg = String::new();
+    write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{WIDTH}" height="{height}" viewBox="0 0 {WIDTH} {height}">"#).unwrap();
+    write!(svg, r#"<title>{}</title>"#, xml_escape(&track.name)).unwrap();
+    write!(svg, r#"<rect width="{WIDTH}" height="{height}" fill="{COLOR_BACKGROUND}"/>"#).unwrap();
+
+    // Bars sharing a color are merged into one path to keep the file small.
+    let mut paths: Vec<(Color, String)> = vec![];
+    for x in 0..WIDTH {
+        let bar = wave_height(wave, x);
+        if bar == 0 { continue; }
+        let color = bar_color(track, sample_types, x);
+        let index = match paths.iter().position(|(c, _)| *c == color) {
+            Some(index) => index,
+            None => { paths.push((color, String::new())); paths.len() - 1 }
+        };
+        write!(paths[index].1, "M{}.5 {}V{}", x, HEIGHT_WAVE, HEIGHT_WAVE - bar).unwrap();
+    }
+    for (color, d) in paths {
+        write!(svg, r#"<path d="{}" stroke="{}"/>"#, d, color.hex()).unwrap();
+    }
+
+    for (lane, sample) in track.samples.iter().enumerate() {
+        let color = sample_types[&sample.r#type].color.hex();
+        let y = HEIGHT_WAVE + lane * HEIGHT_LANE;
+        for occurance in &sample.occurances {
+            let (width, left) = calc_sample_pos(track, occurance);
+            let x = left / 100.0 * WIDTH as f32;
+            let width = width / 100.0 * WIDTH as f32;
+            write!(
+                svg,
+                r#"<rect x="{:.1}" y="{}" width="{:.1}" height="{}" fill="{}" fill-opacity="0.6"><title>{}: {}</title></rect>"#,
+                x, y + 1, width, HEIGHT_LANE - 2, color, xml_escape(&sample.artist), xml_escape(&sample.name),
+            ).unwrap();
+        }
+    }
+
+    let y = HEIGHT_WAVE + height_lanes + HEIGHT_LEGEND / 2;
+    let mut x = 4;
+    for r#type in types {
+        let sample_type = &sample_types[r#type];
+        let color = sample_type.color.hex();
+        write!(svg, r#"<rect x="{}" y="{}" width="10" height="10" fill="{}"/>"#, x, y - 5, color).unwrap();
+        write!(
+            svg,
+            r#"<text x="{}" y="{}" fill="{}" font-family="monospace" font-size="11" dominant-baseline="middle">{}</text>"#,
+            x + 14, y, COLOR_WAVE.hex(), xml_escape(&sample_type.label),
+        ).unwrap();
+        x += 14 + 7 * sample_type.label.chars().count() + 16;
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Bar height in pixels for the given x position.
+fn wave_height(wave: &Wave, x: usize) -> usize {
+    let points = &wave.points;
+    if points.is_empty() { return 0; }
+    let point = points[x * points.len() / WIDTH] as usize;
+    point * HEIGHT_WAVE / 256
+}
+
+/// Every sample playing at x adds its color; bars without samples stay white.
+fn bar_color(track: &Track, sample_types: &SampleTypes, x: usize) -> Color {
+    let duration = track.duration.unwrap_or_else(Duration::zero).num_seconds() as f32;
+    let at = x as f32 / WIDTH as f32 * duration;
+    let mut color: Option<Color> = None;
+    for sample in &track.samples {
+        let is_playing = sample.occurances
+            .iter()
+            .any(|occurs| occurs.from.num_seconds() as f32 <= at && at < occurs.to.num_seconds() as f32);
+        if !is_playing { continue; }
+        let sample_color = sample_types[&sample.r#type].color;
+        color = Some(match color {
+            Some(color) => color.add(&sample_color),
+            None => sample_color,
+        });
+    }
+    color.unwrap_or(COLOR_WAVE)
+}
+
+/// Sample types of a track in order of first appearance.
+fn types_used(track: &Track) -> Vec<&str> {
+    let mut types = vec![];
+    for sample in &track.samples {
+        if !types.contains(&sample.r#type.as_str()) { types.push(sample.r#type.as_str()); }
+    }
+    types
+}
+
+/// Builds a wave from the peak amplitude of every decoded chunk of a track,
+/// keeping the loudest chunk per point and scaling the loudest point to 255.
+pub fn wave_from_peaks(peaks: &[f32]) -> Wave {
+    let count = POINTS.min(peaks.len());
+    let maxima: Vec<f32> = (0..count)
+        .map(|i| {
+            let chunk = &peaks[i * peaks.len() / count..(i + 1) * peaks.len() / count];
+            chunk.iter().copied().fold(0.0, f32::max)
+        })
+        .collect();
+    let loudest = maxima.iter().copied().fold(0.0, f32::max);
+    let points: Vec<u8> = maxima
+        .iter()
+        .map(|peak| if loudest > 0.0 { (peak / loudest * 255.0).round() as u8 } else { 0 })
+        .collect();
+    Wave { length: points.len() as i32, points }
+}
@@ -1,81 +1,119 @@
 use chrono::{NaiveDate, Duration};
-use serde::{Deserialize, de::Visitor};
+use serde::{Deserialize, Serialize, Serializer, de::Visitor};
 
-use crate::{types::music::{Artist, CollectiveMember, Album, Track, TrackArtist, Location, Wave, Sample, SampleOccurance, TrackSample}, parse_name, str_to_duration};
-use std::{io::Error, fs::{read_dir, DirEntry}, collections::{HashMap, HashSet}, hash::Hash, mem, f32::consts::E};
+use crate::{types::{music::{Artist, CollectiveMember, Album, Track, TrackArtist, Location, Wave, Sample, SampleOccurance, TrackSample, TrackStatus, ReleaseType, Playlist}, date::PartialDate}, parse_name, collation_key, str_to_duration, Color};
+use std::{io::{Error, ErrorKind}, fs::{read_dir, DirEntry}, path::Path, collections::{HashMap, HashSet, BTreeMap}, hash::Hash, mem, f32::consts::E};
 use itertools::Itertools;
 
 
 #[derive(Debug)]
 pub struct Data {
-    pub artists: HashMap<String, Artist>,
-    pub albums: HashMap<String, Album>,
-    pub tracks: HashMap<String, Track>,
-    pub countries: HashMap<String, Country>,
+    /// Where the db was read from.
+    pub path: String,
+    pub artists: BTreeMap<String, Artist>,
+    /// Keyed by `artist/album`, the path of their page, as album ids repeat across artists.
+    pub albums: BTreeMap<String, Album>,
+    /// Keyed by `artist/album/track`, the path of their page, as track ids repeat across albums.
+    pub tracks: BTreeMap<String, Track>,
+    pub playlists: BTreeMap<String, Playlist>,
+    /// Ids of aliases to the id of their artist.
+    pub aliases: BTreeMap<String, String>,
+    pub countries: BTreeMap<String, Country>,
+    pub sample_types: SampleTypes,
 }
 
-pub type Features = HashMap<String, Vec<Track>>;
+/// Tracks by the `artist/album` key of their album.
+pub type Features = BTreeMap<String, Vec<Track>>;
 
 impl Data {
-    fn new() -> Data {
+    fn new(path: &str) -> Data {
         let countries = get_countries().unwrap();
+        let sample_types = get_sample_types().unwrap();
         Data {
-            artists: HashMap::new(),
-            albums: HashMap::new(),
-            tracks: HashMap::new(),
+            path: path.to_owned(),
+            artists: BTreeMap::new(),
+            albums: BTreeMap::new(),
+            tracks: BTreeMap::new(),
+            playlists: BTreeMap::new(),
+            aliases: BTreeMap::new(),
             countries,
+            sample_types,
         }
     }
 
-    pub fn get_album(&self, id: &str) -> Album {
-        self.albums.get(id).unwrap().clone()
+    /// Album of an `artist/album` key.
+    pub fn get_album(&self, key: &str) -> Album {
+        self.albums.get(key).unwrap().clone()
     }
 
+    /// Artist of an id or of one of its aliases.
     pub fn get_artist(&self, id: &str) -> Option<Artist> {
+        let id = self.aliases.get(id).map(String::as_str).unwrap_or(id);
         if let Some(artist) = self.artists.get(id) { Some(artist.clone()) } else { None }
     }
 
+    /// Artists that are no collective, by name.
     pub fn get_artists_sorted(&self) -> Vec<Artist> {
-        self.artists
-            .keys()
-            .sorted()
-            .map(|id| self.artists.get(id).unwrap().clone())
-            .filter(|artist| artist.collective_members.is_none())
-            .collect()
+        sort_artists(self.artists.values().filter(|artist| artist.collective_members.is_none()).cloned().collect())
     }
 
+    /// Collectives by name.
     pub fn get_collectives_sorted(&self) -> Vec<Artist> {
-        self.artists
-            .keys()
-            .sorted()
-            .map(|id| self.artists.get(id).unwrap().clone())
-            .filter(|artist| artist.collective_members.is_some())
+        sort_artists(self.artists.values().filter(|artist| artist.collective_members.is_some()).cloned().collect())
+    }
+
+    /// Albums by the year they came out, oldest first and undated albums last.
+    /// Albums of a circa year are counted in that year.
+    pub fn get_albums_by_year(&self) -> Vec<(Option<i32>, Vec<Album>)> {
+        let albums = self.albums.values().cloned().sorted_by(|a, b| a.released.cmp(&b.released).then(a.id.cmp(&b.id)));
+        albums
+            .group_by(|album| album.released.year())
+            .into_iter()
+            .map(|(year, albums)| (year, albums.collect()))
             .collect()
     }
 
+    /// Discography of an artist, oldest release first.
     pub fn get_albums_by(&self, id: &str) -> Vec<Album> {
         self.albums
             .values()
-            .into_iter()
             .filter(|album| album.artist_id == id)
-            .map(|album| album.to_owned())
+            .cloned()
+            .sorted_by(|a, b| a.released.cmp(&b.released).then(a.id.cmp(&b.id)))
             .collect()
     }
 
-    pub fn get_artists_by_country(&self) -> HashMap<String, Vec<Artist>> {
-        let mut result = HashMap::new();
-        for (_, artist) in &self.artists {
+    /// Discography of an artist grouped by release type, oldest release first in each.
+    pub fn get_albums_by_type(&self, id: &str) -> Vec<(ReleaseType, Vec<Album>)> {
+        let mut result: BTreeMap<ReleaseType, Vec<Album>> = BTreeMap::new();
+        for album in self.get_albums_by(id) {
+            result.entry(album.r#type).or_default().push(album);
+        }
+        result.into_iter().collect()
+    }
+
+    /// Artists of every country with artists, by name.
+    pub fn get_artists_by_country(&self) -> BTreeMap<String, Vec<Artist>> {
+        let mut result: BTreeMap<String, Vec<Artist>> = BTreeMap::new();
+        for artist in self.artists.values() {
             if let Some(code) = &artist.country_code {
-                if let None = result.get(code) { result.insert(code.clone(), vec![]); }
-                let artists = result.get_mut(code).unwrap();
-                artists.push(artist.clone())
+                result.entry(code.clone()).or_default().push(artist.clone());
             }
         }
-        result
+        result.into_iter().map(|(code, artists)| (code, sort_artists(artists))).collect()
+    }
+
+    /// Albums with the tracks of an artist, in the order of their release.
+    pub fn albums_by_release(&self, features: &Features) -> Vec<(String, Vec<Track>)> {
+        features
+            .iter()
+            .sorted_by(|(a, _), (b, _)| self.albums[*a].released.cmp(&self.albums[*b].released).then(a.cmp(b)))
+            .map(|(key_album, tracks)| (key_album.to_owned(), tracks.to_owned()))
+            .collect()
     }
 
     pub fn get_tracks_by(&self, id_artist: &str) -> Features {
-        let mut result = HashMap::new();
+        let mut result = Features::new();
         let has_feature = |track: &Track| track.artists
             .iter()
             .any(|artist| artist.id == id_artist);
@@ -83,28 +121,23 @@ impl Data {
         for (id, track) in &self.tracks {
             if !has_feature(track) { continue; }
 
-            if let None = result.get(&track.album_id) { result.insert(track.album_id.clone(), vec![]); }
-            let album_tracks = result.get_mut(&track.album_id).unwrap();
-            album_tracks.push(track.clone());
+            result.entry(track.album_key()).or_default().push(track.clone());
         }
         result
     }
 
     /// These exclude albums by the same artist.
     pub fn get_features_by(&self, id_artist: &str) -> Features {
-        let mut result = HashMap::new();
+        let mut result = Features::new();
         let has_feature = |track: &Track| track.artists
             .iter()
             .any(|artist| artist.id == id_artist);
 
         for (id, track) in &self.tracks {
             if !has_feature(track) { continue; }
-            let album = self.get_album(&track.album_id);
-            if album.artist_id == id_artist { continue; }
+            if track.artist_id == id_artist { continue; }
 
-            if let None = result.get(&track.album_id) { result.insert(track.album_id.clone(), vec![]); }
-            let album_tracks = result.get_mut(&track.album_id).unwrap();
-            album_tracks.push(track.clone());
+            result.entry(track.album_key()).or_default().push(track.clone());
         }
         result
     }
@@ -130,10 +163,27 @@ impl Data {
             .collect()
     }
 
-    pub fn get_album_artist_ids(&self, id_album: &str) -> Vec<String> {
+    /// Artists sharing tracks with the given one, most shared tracks first.
+    pub fn get_collaborators(&self, id_artist: &str) -> Vec<(String, usize)> {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for track in self.tracks.values() {
+            if !track.artists.iter().any(|artist| artist.id == id_artist) { continue; }
+            let ids: HashSet<&String> = track.artists.iter().map(|artist| &artist.id).collect();
+            for id in ids {
+                if id == id_artist { continue; }
+                *counts.entry(id.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .sorted_by(|(id_a, a), (id_b, b)| b.cmp(a).then(id_a.cmp(id_b)))
+            .collect()
+    }
+
+    pub fn get_album_artist_ids(&self, key_album: &str) -> Vec<String> {
         self.tracks
             .values()
-            .filter(|track| track.album_id == id_album)
+            .filter(|track| track.album_key() == key_album)
             .flat_map(
                 |track|
                     track.artists
@@ -141,86 +191,142 @@ impl Data {
                         .map(|artist| artist.id.clone())
                 )
             
-            .collect::<HashSet<String>>()
-            .iter()
-            .map(|id| id.to_owned())
+            .unique()
+            .sorted()
             .collect::<Vec<String>>()
     }
 
-    pub fn get_tracks_in_album(&self, id_album: &str) -> HashMap<u8, Track> {
-        let mut result = HashMap::new();
+    pub fn get_tracks_in_album(&self, key_album: &str) -> BTreeMap<u8, Track> {
+        let mut result = BTreeMap::new();
         for (id_track, track) in &self.tracks {
-            if track.album_id != id_album { continue; }
+            if track.album_key() != key_album { continue; }
             result.insert(track.position, track.clone());
         }
         result
     }
     
+    /// Tracks of an album ordered by position.
+    pub fn get_tracks_in_album_sorted(&self, key_album: &str) -> Vec<Track> {
+        self.get_tracks_in_album(key_album)
+            .into_values()
+            .sorted_by_key(|track| track.position)
+            .collect()
+    }
+
     pub fn get_sample(&self, id_sample: &str) -> Option<Sample> {
         None
     }
 }
 
 
-#[derive(Debug, Deserialize)]
-struct YamlArtist {
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct YamlArtist {
     pub name: String,
     pub image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub logo: Option<String>,
     pub urls: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub collective_members: Option<Vec<YamlCollectiveMember>>,
+    /// Other names the artist releases under, references to them lead to this artist.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aliases: Option<Vec<String>>,
+    /// The name in other scripts or languages, by language code like `ru`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub names: Option<BTreeMap<String, String>>,
 }
 
-#[derive(Debug, Deserialize)]
-struct YamlCollectiveMember {
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct YamlPlaylist {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tracks as `artist/album/track`, the path of their page.
+    pub tracks: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct YamlCollectiveMember {
     pub name: String,
     /// Year of joined.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub joined: Option<i32>,
     /// Year of left.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub left: Option<i32>,
 
 }
 
-#[derive(Debug, Deserialize)]
-struct YamlAlbum {
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct YamlAlbum {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub artists: Option<Vec<String>>,
     pub genre: String,
     // pub duration: String,
-    pub released: NaiveDate,
+    #[serde(default)]
+    pub released: PartialDate,
+    /// Taken from the track count when not given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<ReleaseType>,
     pub cover: String,
+    #[serde(serialize_with = "serialize_tracks")]
     pub tracks: HashMap<String, YamlTrack>,
     pub track_count: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub urls: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct YamlTrack {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<String>,
+    #[serde(default, skip_serializing_if = "TrackStatus::is_released")]
+    pub status: TrackStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub artists: Option<Vec<YamlTrackArtist>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub artist: Option<String>,
     pub location: Vec<YamlLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sample: Option<Vec<YamlSample>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub lyrics: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub wave: Option<Wave>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct YamlTrackArtist {
     pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub r#for: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct YamlLocation {
     pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub at: Option<String>,
+    /// Id of the track on the platform, Bandcamp players need it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct YamlSample {
     pub media: String,
     pub artist: String,
@@ -229,11 +335,23 @@ pub struct YamlSample {
     pub occurs: Vec<YamlSampleOccurance>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct YamlSampleOccurance {
-    from: String,
-    to: String,
-    at: String,
+    pub from: String,
+    pub to: String,
+    pub at: String,
+}
+
+/// Writes tracks ordered by position, with the position as a number.
+fn serialize_tracks<S>(tracks: &HashMap<String, YamlTrack>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer {
+    let sorted: BTreeMap<u8, &YamlTrack> = tracks
+        .iter()
+        .map(|(position, track)| (position.parse().unwrap(), track))
+        .collect();
+    sorted.serialize(serializer)
 }
 
 // #[derive(Debug, Clone)]
@@ -265,6 +383,14 @@ impl<'de> Visitor<'de> for VisitorWave {
     }
 }
 
+impl Serialize for Wave {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer {
+        serializer.serialize_str(&base64::encode(&self.points))
+    }
+}
+
 impl<'de> Deserialize<'de> for Wave {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
         where
@@ -278,21 +404,74 @@ pub struct Country {
     pub name: String,
     pub code: String,
     pub emoji: String,
+    /// Remote url of the flag svg.
+    pub image: String,
+    /// Rough center used to place the country on the map.
+    #[serde(skip)]
+    pub position: Option<Position>,
 }
 
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct Position {
+    pub lat: f32,
+    pub lon: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CountryPosition {
+    code: String,
+    #[serde(flatten)]
+    position: Position,
+}
+
+/// A kind of sample (vocals, beat, ...), every `TrackSample.type` has to be one of these.
+#[derive(Debug, Deserialize, Clone)]
+pub struct SampleType {
+    pub id: String,
+    pub label: String,
+    /// Used for the wave and the sample legend.
+    #[serde(deserialize_with = "deserialize_color")]
+    pub color: Color,
+}
+
+pub type SampleTypes = BTreeMap<String, SampleType>;
+
+fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
+    where
+        D: serde::Deserializer<'de> {
+    let hex = String::deserialize(deserializer)?;
+    Color::from_hex(&hex).ok_or_else(|| serde::de::Error::custom(format!("invalid color: {}", hex)))
+}
+
+/// Reads the db. Data that breaks the checks of the db is an `InvalidData` error,
+/// artist and album files that are missing or do not parse still panic.
 pub fn read_data(path: &str) -> Result<Data, Error> {
-    let mut data = Data::new();
+    let mut data = Data::new(path);
     read_artists(path, &mut data)?;
+    resolve_aliases(&mut data)?;
+    read_playlists(path, &mut data)?;
     // let artists = get_artists(path, &mut data)?;
     Ok(data)
 }
 
+fn invalid_data(message: String) -> Error {
+    Error::new(ErrorKind::InvalidData, message)
+}
+
+/// Names of the entries of a folder, sorted so the db is read in the same order everywhere.
+pub(crate) fn file_names(path: &str) -> Result<Vec<String>, Error> {
+    let mut names = vec![];
+    for entry in read_dir(path)? {
+        names.push(entry?.file_name().into_string().unwrap());
+    }
+    names.sort();
+    Ok(names)
+}
+
 // Reads the git submodule and returns it as a Data type.
 fn read_artists(path: &str, data: &mut Data) -> Result<(), Error>{
     let path_artists = path.to_owned() + "artists/";
-    let artists = read_dir(&path_artists)?;
-    for entry in artists {
-        let artist_id = entry.unwrap().file_name().into_string().unwrap();
+    for artist_id in file_names(&path_artists)? {
         let path = path_artists.to_owned() + &artist_id;
         let artist = read_artist(&path, data, &artist_id)?;
         data.artists.insert(artist_id.clone(), artist);
@@ -302,6 +481,65 @@ fn read_artists(path: &str, data: &mut Data) -> Result<(), Error>{
     Ok(())
 }
 
+/// Orders artists by name, then by id for artists of the same name.
+pub fn sort_artists(artists: Vec<Artist>) -> Vec<Artist> {
+    artists
+        .into_iter()
+        .sorted_by(|a, b| collation_key(&a.name).cmp(&collation_key(&b.name)).then(a.id.cmp(&b.id)))
+        .collect()
+}
+
+/// Points references to aliases in tracks and collectives to their artist.
+/// An alias can neither be the id of another artist nor belong to two artists.
+fn resolve_aliases(data: &mut Data) -> Result<(), Error> {
+    for artist in data.artists.values() {
+        for alias in &artist.aliases {
+            let id = parse_name(alias);
+            if id == artist.id { continue; }
+            if data.artists.contains_key(&id) {
+                return Err(invalid_data(format!("Alias \"{}\" of {} is the id of another artist", alias, artist.id)));
+            }
+            if let Some(taken) = data.aliases.insert(id, artist.id.to_owned()) {
+                return Err(invalid_data(format!("Alias \"{}\" belongs to both {} and {}", alias, taken, artist.id)));
+            }
+        }
+    }
+    let aliases = &data.aliases;
+    let resolve = |id: &mut String| if let Some(canonical) = aliases.get(id) { *id = canonical.to_owned() };
+    for track in data.tracks.values_mut() {
+        track.artists.iter_mut().for_each(|artist| resolve(&mut artist.id));
+    }
+    for artist in data.artists.values_mut() {
+        artist.collective_members.iter_mut().flatten().for_each(|member| resolve(&mut member.id));
+    }
+    Ok(())
+}
+
+/// Reads the playlists, every track they list has to exist.
+fn read_playlists(path: &str, data: &mut Data) -> Result<(), Error> {
+    let path_playlists = path.to_owned() + "playlists/";
+    if !Path::new(&path_playlists).exists() { return Ok(()); }
+    for path_file in file_names(&path_playlists)? {
+        let Some(id) = path_file.strip_suffix(".yml") else { continue };
+        let path = path_playlists.to_owned() + &path_file;
+        let file = std::fs::File::open(&path)?;
+        let yaml: YamlPlaylist = serde_yaml::from_reader(file).map_err(|error| invalid_data(format!("Err reading: {}: {}", path, error)))?;
+
+        let tracks = yaml.tracks
+            .iter()
+            .map(|reference| find_track(data, reference).ok_or_else(|| invalid_data(format!("Unknown track \"{}\" in playlist: {} (write tracks as artist/album/track)", reference, id))))
+            .collect::<Result<_, _>>()?;
+        let playlist = Playlist { id: id.to_owned(), name: yaml.name, description: yaml.description, tracks };
+        data.playlists.insert(id.to_owned(), playlist);
+    }
+    Ok(())
+}
+
+/// Track of an `artist/album/track` reference.
+fn find_track(data: &Data, reference: &str) -> Option<Track> {
+    data.tracks.get(reference).cloned()
+}
+
 fn read_artist(path: &str, data: &mut Data, artist_id: &str) -> Result<Artist, Error> {
     let read_year = |year: Option<i32>| -> Option<NaiveDate> { year.map(|year| NaiveDate::from_yo_opt(year, 1).unwrap()) };
     let yaml = get_artist_data(path)?;
@@ -324,6 +562,8 @@ fn read_artist(path: &str, data: &mut Data, artist_id: &str) -> Result<Artist, E
         collective_members: colletive_members,
         urls: yaml.urls,
         logo_url: yaml.logo,
+        aliases: yaml.aliases.unwrap_or_default(),
+        names: yaml.names.unwrap_or_default(),
     };
     Ok(artist)
 }
@@ -336,17 +576,15 @@ fn get_artist_data(dir_artist: &str) -> Result<YamlArtist, Error> {
 }
 
 fn read_albums(path: &str, data: &mut Data, id_artist: &str) -> Result<(), Error> {
-    let albums = read_dir(path)?;
-    for entry in albums {
-        let path_file = entry.unwrap().file_name().into_string().unwrap();
+    for path_file in file_names(path)? {
         if path_file.starts_with('.') { continue };
 
         let (id_album, _) = path_file.split_once('.').unwrap();
         let path = path.to_owned() + "/" + &path_file;
         let yaml = get_album_data(&path)?;
         let track_count = yaml.track_count;
-        let album_urls = if let Some(urls) = yaml.urls { urls } else {
-            if track_count > 1 { panic!("{} for albums, urls are required", path) }
+        // Albums without urls are listed on the contribute page, singles use their track locations.
+        let album_urls = if let Some(urls) = yaml.urls { urls } else if track_count > 1 { vec![] } else {
             yaml.tracks.get("1").unwrap().location.iter().map(|loc| loc.url.clone()).collect()
         };
         let album = Album {
@@ -356,11 +594,12 @@ fn read_albums(path: &str, data: &mut Data, id_artist: &str) -> Result<(), Error
             genres: vec![yaml.genre],
             duration: Duration::seconds(0),
             released: yaml.released,
+            r#type: yaml.r#type.unwrap_or_else(|| ReleaseType::from_track_count(track_count)),
             cover_url: yaml.cover,
             track_count: track_count,
             urls: album_urls,
         };
-        data.albums.insert(id_album.to_owned(), album.clone());
+        data.albums.insert(album.key(), album.clone());
 
         for (position, track) in &yaml.tracks {
             read_track(data, id_album, &album, position, track)?;
@@ -394,17 +633,22 @@ fn read_track(data: &mut Data, album_id: &str, album: &Album, position_str: &str
     let locations = yaml.location.iter().map(|location| {
         let url = location.url.to_owned();
         let at = if let Some(time) = &location.at { Some(str_to_duration(&time)) } else { None };
-        Location { url, at, } 
+        Location { url, at, id: location.id.to_owned() }
     }).collect();
-    let duration = if let Some(duration) = &yaml.duration {
-        str_to_duration(&duration)
-    } else {
-        panic!("Duration not found for: {}/{}/{}", album.artist_id, album.id, name)
-    };
+    let duration = yaml.duration.as_ref().map(|duration| str_to_duration(duration));
     
     let position: u8 = position_str.parse().unwrap();
     let wave = if let Some(wave) = &yaml.wave { Some(wave.clone()) } else { None };
     let samples = if let Some(samples) = &yaml.sample {
+        for sample in samples {
+            if !data.sample_types.contains_key(&sample.r#type) {
+                let known = data.sample_types.keys().sorted().join(", ");
+                return Err(invalid_data(format!("Unknown sample type \"{}\" for: {}/{}/{} (known: {})", sample.r#type, album.artist_id, album.id, name, known)));
+            }
+            if duration.is_none() && sample.occurs.iter().any(|occurs| occurs.to == "end") {
+                return Err(invalid_data(format!("Sample \"{}\" lasts to the end of a track without duration: {}/{}/{}", sample.name, album.artist_id, album.id, name)));
+            }
+        }
         samples
             .iter()
             .map(|sample| TrackSample {
@@ -417,7 +661,7 @@ fn read_track(data: &mut Data, album_id: &str, album: &Album, position_str: &str
                     .iter()
                     .map(|occurs| SampleOccurance {
                         from: str_to_duration(&occurs.from),
-                        to: if occurs.to == "end" { duration } else { str_to_duration(&occurs.to) },
+                        to: if occurs.to == "end" { duration.unwrap() } else { str_to_duration(&occurs.to) },
                         at: str_to_duration(&occurs.at),
                     })
                     .collect(), })
@@ -431,25 +675,49 @@ fn read_track(data: &mut Data, album_id: &str, album: &Album, position_str: &str
         artist_id: album.artist_id.to_owned(),
         album_id: album_id.to_owned(),
         duration,
+        status: yaml.status,
         artists,
         locations,
         samples,
         wave,
+        lyrics: yaml.lyrics.clone(),
     };
 
-    data.tracks.insert(id_track, track);
+    let key = format!("{}/{}/{}", album.artist_id, album.id, id_track);
+    if let Some(taken) = data.tracks.get(&key) {
+        return Err(invalid_data(format!("Tracks \"{}\" and \"{}\" both get the id \"{}\" in: {}/{}, rename one of them", taken.name, name, id_track, album.artist_id, album.id)));
+    }
+    data.tracks.insert(key, track);
     
     Ok(())
 }
 
 
-pub fn get_countries() -> Result<HashMap<String, Country>, Error> {
+pub fn get_countries() -> Result<BTreeMap<String, Country>, Error> {
     let file = std::fs::File::open("./lib/countries.json")?;
     let countries: Vec<Country> = serde_json::from_reader(file).unwrap();
-    let mut result = HashMap::new();
+    let mut result = BTreeMap::new();
     for country in countries {
         result.insert(country.code.clone(), country);
     }
+
+    let file = std::fs::File::open("./lib/country_positions.json")?;
+    let positions: Vec<CountryPosition> = serde_json::from_reader(file).unwrap();
+    for CountryPosition { code, position } in positions {
+        let country = result.get_mut(&code).unwrap_or_else(|| panic!("Position for unknown country: {}", code));
+        country.position = Some(position);
+    }
+    Ok(result)
+}
+
+pub fn get_sample_types() -> Result<SampleTypes, Error> {
+    let file = std::fs::File::open("./lib/sample_types.json")?;
+    let sample_types: Vec<SampleType> = serde_json::from_reader(file).unwrap();
+    let mut result = BTreeMap::new();
+    for sample_type in sample_types {
+        let id = sample_type.id.clone();
+        if result.insert(id.clone(), sample_type).is_some() { panic!("Sample type listed twice: {}", id) }
+    }
     Ok(result)
 }
 
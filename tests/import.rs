@@ -0,0 +1,93 @@
+use std::fs::{create_dir_all, read_to_string, remove_dir_all, write};
+
+use site::{cli::Args, import::bandcamp::import_bandcamp, yaml::YamlAlbum};
+
+const TRALBUM: &str = r#"{
+    "artist": "DJ Smokey",
+    "url": "https://djsmokey.bandcamp.com/album/smoked-out",
+    "art_id": 1234567,
+    "album_release_date": "20 Apr 2016 00:00:00 GMT",
+    "current": { "title": "Smoked Out {Tape}", "release_date": null },
+    "trackinfo": [
+        { "title": "Lean On Me", "track_num": 1, "duration": 125.4, "title_link": "/track/lean-on-me", "track_id": 42, "artist": null },
+        { "title": "Ghost Ride", "track_num": 2, "duration": 0.0, "title_link": "/track/ghost-ride", "track_id": 43, "artist": "MC Lean" },
+        { "title": "Outro", "track_num": 3, "duration": 3725.0, "title_link": null, "track_id": null, "artist": "DJ Smokey" }
+    ]
+}"#;
+
+/// A db with one artist, removed again by `remove_dir_all`.
+fn db(name: &str) -> String {
+    let path_db = std::env::temp_dir().join(format!("site-import-{}-{}/", name, std::process::id()));
+    let path_db = path_db.to_str().unwrap().to_owned();
+    let path_artist = path_db.to_owned() + "artists/dj-smokey/";
+    create_dir_all(&path_artist).unwrap();
+    write(path_artist + ".artist.yml", "name: DJ Smokey\nimage: https://example.com/smokey.jpg\nurls: []\n").unwrap();
+    path_db
+}
+
+fn import(path_db: &str, html: &str) -> YamlAlbum {
+    let path_page = path_db.to_owned() + "page.html";
+    write(&path_page, html).unwrap();
+    let path = import_bandcamp(path_db, &Args::parse(&[path_page], &[])).unwrap();
+    assert_eq!(path, path_db.to_owned() + "artists/dj-smokey/smoked-out-tape.yml");
+    serde_yaml::from_str(&read_to_string(path).unwrap()).unwrap()
+}
+
+fn assert_album(album: &YamlAlbum) {
+    assert_eq!(album.name, "Smoked Out {Tape}");
+    assert_eq!(album.genre, "memphis rap");
+    assert_eq!(album.released.to_string(), "2016-04-20");
+    assert_eq!(album.cover, "https://f4.bcbits.com/img/a0001234567_10.jpg");
+    assert_eq!(album.track_count, 3);
+    assert_eq!(album.urls, Some(vec!["https://djsmokey.bandcamp.com/album/smoked-out".to_owned()]));
+
+    let lean = &album.tracks["1"];
+    assert_eq!(lean.duration.as_deref(), Some("2:05"));
+    assert!(lean.artists.is_none());
+    assert_eq!(lean.location[0].url, "https://djsmokey.bandcamp.com/track/lean-on-me");
+    assert_eq!(lean.location[0].id.as_deref(), Some("42"));
+
+    // Tracks that are not out yet have no duration, guests are kept by id.
+    let ghost = &album.tracks["2"];
+    assert_eq!(ghost.duration, None);
+    assert_eq!(ghost.artists.as_ref().unwrap()[0].id, "mc-lean");
+
+    // The album artist is not repeated on a track, tracks without a link have no location.
+    let outro = &album.tracks["3"];
+    assert_eq!(outro.duration.as_deref(), Some("1:02:05"));
+    assert!(outro.artists.is_none());
+    assert!(outro.location.is_empty());
+}
+
+#[test]
+fn bandcamp_page_with_data_tralbum() {
+    let path_db = db("data-tralbum");
+    let escaped = TRALBUM.replace('&', "&amp;").replace('"', "&quot;");
+    let html = format!(
+        r#"<html><script data-tralbum="{}"></script><a class="tag" href="/tag/memphis-rap">Memphis Rap</a><a class="tag">phonk</a></html>"#,
+        escaped,
+    );
+    assert_album(&import(&path_db, &html));
+    remove_dir_all(&path_db).unwrap();
+}
+
+#[test]
+fn bandcamp_page_with_tralbum_script() {
+    let path_db = db("tralbum-script");
+    let html = format!(
+        "<html><script>\nvar TralbumData = {};\nvar EmbedData = {{}};\n</script><a class=\"tag\">Memphis Rap</a></html>",
+        TRALBUM,
+    );
+    assert_album(&import(&path_db, &html));
+    remove_dir_all(&path_db).unwrap();
+}
+
+#[test]
+fn bandcamp_page_without_tralbum() {
+    let path_db = db("no-tralbum");
+    let path_page = path_db.to_owned() + "page.html";
+    write(&path_page, "<html>not a bandcamp page</html>").unwrap();
+    let error = import_bandcamp(&path_db, &Args::parse(&[path_page], &[])).unwrap_err();
+    assert!(error.to_string().contains("no TralbumData found"));
+    remove_dir_all(&path_db).unwrap();
+}
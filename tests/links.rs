@@ -0,0 +1,112 @@
+use std::{fs::{create_dir_all, read_to_string, remove_dir_all, write}, io::{BufRead, BufReader, Write}, net::TcpListener, sync::{Arc, Mutex}, thread, time::{Duration, Instant}};
+
+use chrono::Local;
+use site::{links::{check, collect, report, Cache, Dead, Options, StandIn}, yaml::read_data};
+
+const ARTIST: &str = "
+name: Stand In
+image: https://img.test/stand-in.jpg
+urls:
+  - https://ok.test/stand-in
+  - https://dead.test/stand-in
+";
+
+const ALBUM: &str = "
+name: Checked
+genre: phonk
+cover: https://ok.test/cover.jpg
+track_count: 1
+urls:
+  - https://dead.test/stand-in
+tracks:
+  1:
+    name: Limited
+    location:
+      - url: https://limited.test/track
+";
+
+/// Answers `404` under `/dead.test/`, `429` under `/limited.test/` and `200` for
+/// anything else, and returns the paths it was asked for.
+fn serve() -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let server = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(vec![]));
+    let seen = requests.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut lines = BufReader::new(&stream).lines().map(Result::unwrap);
+            let path = lines.next().unwrap().split(' ').nth(1).unwrap().to_owned();
+            lines.take_while(|line| !line.is_empty()).for_each(drop);
+            let status = if path.starts_with("/dead.test/") {
+                "404 Not Found"
+            } else if path.starts_with("/limited.test/") {
+                "429 Too Many Requests"
+            } else {
+                "200 OK"
+            };
+            seen.lock().unwrap().push(path);
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status).unwrap();
+        }
+    });
+    (server, requests)
+}
+
+fn dead(dead: &[Dead]) -> Vec<(&str, &str, &str)> {
+    dead.iter().map(|dead| (dead.file.as_str(), dead.url.as_str(), dead.reason.as_str())).collect()
+}
+
+#[test]
+fn dead_links_are_reported_per_file_and_cached() {
+    let path_db = std::env::temp_dir().join(format!("site-links-{}/", std::process::id()));
+    let path_db = path_db.to_str().unwrap().to_owned();
+    let path_artist = path_db.to_owned() + "artists/stand-in/";
+    create_dir_all(&path_artist).unwrap();
+    write(path_artist.to_owned() + ".artist.yml", ARTIST).unwrap();
+    write(path_artist.to_owned() + "checked.yml", ALBUM).unwrap();
+
+    let data = read_data(&path_db).unwrap();
+    let links = collect(&data);
+    assert_eq!(links.len(), 6);
+
+    let (server, requests) = serve();
+    let fetcher = StandIn::new(&server);
+    let options = Options { delay: Duration::from_millis(50), max_age: 7 };
+    let file_artist = path_artist.to_owned() + ".artist.yml";
+    let file_album = path_artist.to_owned() + "checked.yml";
+    let expected = vec![
+        (file_artist.as_str(), "https://dead.test/stand-in", "404"),
+        (file_album.as_str(), "https://dead.test/stand-in", "404"),
+    ];
+
+    // Every url is asked for once, even when two files link it, with the delay in between.
+    let mut cache = Cache::default();
+    let start = Instant::now();
+    let found = check(&links, &fetcher, &mut cache, &options);
+    assert!(start.elapsed() >= options.delay * 4);
+    assert_eq!(dead(&found), expected);
+    assert_eq!(requests.lock().unwrap().len(), 5);
+    assert_eq!(
+        report(&found),
+        format!("{}\n    404 https://dead.test/stand-in\n{}\n    404 https://dead.test/stand-in\n", file_artist, file_album),
+    );
+
+    // Cached statuses are used, only the rate limited url is asked for again.
+    requests.lock().unwrap().clear();
+    let found = check(&links, &fetcher, &mut cache, &options);
+    assert_eq!(dead(&found), expected);
+    assert_eq!(*requests.lock().unwrap(), vec!["/limited.test/track"]);
+
+    // Statuses older than `max_age` days are checked again.
+    let path_cache = path_db.to_owned() + "cache.json";
+    cache.write(&path_cache).unwrap();
+    let today = Local::now().date_naive().to_string();
+    write(&path_cache, read_to_string(&path_cache).unwrap().replace(&today, "2000-01-01")).unwrap();
+    let mut cache = Cache::read(&path_cache).unwrap();
+    requests.lock().unwrap().clear();
+    let found = check(&links, &fetcher, &mut cache, &options);
+    assert_eq!(dead(&found), expected);
+    assert_eq!(requests.lock().unwrap().len(), 5);
+
+    remove_dir_all(&path_db).unwrap();
+}